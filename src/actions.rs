@@ -0,0 +1,323 @@
+use std::fmt::Display;
+
+use crate::{keybindings::Action, state::updates::*};
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum Category {
+    Selection,
+    File,
+    Folder,
+    Search,
+    Preview,
+    Trash,
+    Date,
+    Sort,
+    Misc,
+}
+
+/// An action that can be referred to by a stable name, e.g. from the config file.
+pub struct NamedAction {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub category: Category,
+    pub takes_count: bool,
+    pub function: Action,
+}
+
+pub static ACTIONS: &[NamedAction] = &[
+    NamedAction {
+        name: "selection.down",
+        description: "move the selection down",
+        category: Category::Selection,
+        takes_count: true,
+        function: selection_down,
+    },
+    NamedAction {
+        name: "selection.up",
+        description: "move the selection up",
+        category: Category::Selection,
+        takes_count: true,
+        function: selection_up,
+    },
+    NamedAction {
+        name: "selection.top",
+        description: "select the first file (or the count-th)",
+        category: Category::Selection,
+        takes_count: true,
+        function: selection_top,
+    },
+    NamedAction {
+        name: "selection.bottom",
+        description: "select the last file (or the count-th from the end)",
+        category: Category::Selection,
+        takes_count: true,
+        function: selection_bottom,
+    },
+    NamedAction {
+        name: "selection.visual",
+        description: "start or stop selecting a range of files",
+        category: Category::Selection,
+        takes_count: false,
+        function: toggle_visual,
+    },
+    NamedAction {
+        name: "file.open",
+        description: "open the selected files in the editor or enter the selected folder",
+        category: Category::File,
+        takes_count: false,
+        function: open_selected,
+    },
+    NamedAction {
+        name: "file.delete",
+        description:
+            "move the selected files (or the count files from the selection on) to the trash",
+        category: Category::File,
+        takes_count: true,
+        function: delete_file,
+    },
+    NamedAction {
+        name: "file.rename",
+        description: "rename the selected file, starting in front of the extension",
+        category: Category::File,
+        takes_count: false,
+        function: rename,
+    },
+    NamedAction {
+        name: "file.rename_end",
+        description: "rename the selected file, starting after the extension",
+        category: Category::File,
+        takes_count: false,
+        function: rename_after_extension,
+    },
+    NamedAction {
+        name: "file.yank",
+        description: "copy the paths of the selected files to the clipboard",
+        category: Category::File,
+        takes_count: false,
+        function: yank_paths,
+    },
+    NamedAction {
+        name: "file.undo",
+        description: "undo the last change to the files (or the last count changes)",
+        category: Category::File,
+        takes_count: true,
+        function: undo,
+    },
+    NamedAction {
+        name: "file.redo",
+        description: "redo the last undone change (or the last count ones)",
+        category: Category::File,
+        takes_count: true,
+        function: redo,
+    },
+    NamedAction {
+        name: "folder.parent",
+        description: "go to the parent folder",
+        category: Category::Folder,
+        takes_count: false,
+        function: leave_folder,
+    },
+    NamedAction {
+        name: "search.start",
+        description: "search for a file name in the current folder",
+        category: Category::Search,
+        takes_count: false,
+        function: start_search,
+    },
+    NamedAction {
+        name: "search.next",
+        description: "select the next search match",
+        category: Category::Search,
+        takes_count: true,
+        function: search_next,
+    },
+    NamedAction {
+        name: "search.previous",
+        description: "select the previous search match",
+        category: Category::Search,
+        takes_count: true,
+        function: search_previous,
+    },
+    NamedAction {
+        name: "search.content",
+        description: "search the contents of all notes",
+        category: Category::Search,
+        takes_count: false,
+        function: start_content_search,
+    },
+    NamedAction {
+        name: "search.fuzzy",
+        description: "fuzzy find a note anywhere below the notes folder",
+        category: Category::Search,
+        takes_count: false,
+        function: open_finder,
+    },
+    NamedAction {
+        name: "preview.down",
+        description: "scroll the preview down by a line (or count lines)",
+        category: Category::Preview,
+        takes_count: true,
+        function: scroll_preview_down,
+    },
+    NamedAction {
+        name: "preview.up",
+        description: "scroll the preview up by a line (or count lines)",
+        category: Category::Preview,
+        takes_count: true,
+        function: scroll_preview_up,
+    },
+    NamedAction {
+        name: "preview.half_page_down",
+        description: "scroll the preview down by half a page",
+        category: Category::Preview,
+        takes_count: false,
+        function: scroll_preview_half_page_down,
+    },
+    NamedAction {
+        name: "preview.half_page_up",
+        description: "scroll the preview up by half a page",
+        category: Category::Preview,
+        takes_count: false,
+        function: scroll_preview_half_page_up,
+    },
+    NamedAction {
+        name: "preview.wrap",
+        description: "wrap long lines in the preview or stop doing so",
+        category: Category::Preview,
+        takes_count: false,
+        function: toggle_wrap,
+    },
+    NamedAction {
+        name: "trash.open",
+        description: "show the trash",
+        category: Category::Trash,
+        takes_count: false,
+        function: open_trash,
+    },
+    NamedAction {
+        name: "trash.restore",
+        description: "restore the selected file in the trash to where it was deleted",
+        category: Category::Trash,
+        takes_count: false,
+        function: restore_from_trash,
+    },
+    NamedAction {
+        name: "trash.purge",
+        description: "permanently delete the selected file in the trash",
+        category: Category::Trash,
+        takes_count: false,
+        function: purge_from_trash,
+    },
+    NamedAction {
+        name: "date.forward",
+        description: "open the note of the day count days from today",
+        category: Category::Date,
+        takes_count: true,
+        function: open_rel_date_fwd,
+    },
+    NamedAction {
+        name: "date.backward",
+        description: "open the note of the day count days before today",
+        category: Category::Date,
+        takes_count: true,
+        function: open_rel_date_bwd,
+    },
+    NamedAction {
+        name: "sort.name",
+        description: "sort by name",
+        category: Category::Sort,
+        takes_count: false,
+        function: sort_by_name,
+    },
+    NamedAction {
+        name: "sort.ctime",
+        description: "sort by creation time",
+        category: Category::Sort,
+        takes_count: false,
+        function: sort_by_ctime,
+    },
+    NamedAction {
+        name: "sort.mtime",
+        description: "sort by modification time",
+        category: Category::Sort,
+        takes_count: false,
+        function: sort_by_mtime,
+    },
+    NamedAction {
+        name: "sort.size",
+        description: "sort by size",
+        category: Category::Sort,
+        takes_count: false,
+        function: sort_by_size,
+    },
+    NamedAction {
+        name: "sort.natural",
+        description: "sort naturally (\"1\" < \"10\")",
+        category: Category::Sort,
+        takes_count: false,
+        function: sort_by_natural,
+    },
+    NamedAction {
+        name: "sort.reverse",
+        description: "reverse the sort order",
+        category: Category::Sort,
+        takes_count: false,
+        function: reverse_sort,
+    },
+    NamedAction {
+        name: "command",
+        description: "run a command by name, like :sort mtime",
+        category: Category::Misc,
+        takes_count: false,
+        function: start_command,
+    },
+    NamedAction {
+        name: "palette",
+        description: "find and run any action",
+        category: Category::Misc,
+        takes_count: false,
+        function: open_palette,
+    },
+    NamedAction {
+        name: "help",
+        description: "show all key bindings",
+        category: Category::Misc,
+        takes_count: false,
+        function: show_help,
+    },
+    NamedAction {
+        name: "messages",
+        description: "show recent messages and errors",
+        category: Category::Misc,
+        takes_count: false,
+        function: show_messages,
+    },
+    NamedAction {
+        name: "nop",
+        description: "do nothing (can be used to unbind keys)",
+        category: Category::Misc,
+        takes_count: false,
+        function: |_, _, _| Ok(()),
+    },
+];
+
+pub fn by_name(name: &str) -> Option<&'static NamedAction> {
+    ACTIONS.iter().find(|action| action.name == name)
+}
+
+impl Display for Category {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Category::Selection => "selection",
+            Category::File => "file",
+            Category::Folder => "folder",
+            Category::Search => "search",
+            Category::Preview => "preview",
+            Category::Trash => "trash",
+            Category::Date => "date",
+            Category::Sort => "sort",
+            Category::Misc => "misc",
+        };
+        write!(f, "{}", name)
+    }
+}
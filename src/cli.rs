@@ -0,0 +1,153 @@
+//! Parsing the command line arguments.
+
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
+
+use crate::{sorting::Sorting, trash};
+
+pub const USAGE: &str = "Usage: notes-tui [OPTIONS] <FOLDER>";
+
+pub const HELP: &str = "\
+Browse and edit the notes in FOLDER.
+
+Usage: notes-tui [OPTIONS] <FOLDER>
+
+Options:
+  -s, --sort <ORDER>     sort by name, ctime, mtime, size or natural [default: natural]
+  -r, --reverse          reverse the sort order
+  -e, --editor <EDITOR>  the editor for notes [default: $VISUAL or $EDITOR]
+  -c, --config <FILE>    the config file [default: $XDG_CONFIG_HOME/notes-tui/config.toml]
+      --select <FILE>    start with FILE selected, relative to FOLDER or the current folder
+  -h, --help             print this help
+  -V, --version          print the version
+";
+
+pub enum Command {
+    Run(Args),
+    Help,
+    Version,
+}
+
+pub struct Args {
+    pub folder: PathBuf,
+    pub sorting: Sorting,
+    pub reverse: bool,
+    pub editor: Option<OsString>,
+    pub config: Option<PathBuf>,
+    pub select: Option<PathBuf>,
+}
+
+/// Parses the arguments after the program name. Values can follow their option
+/// either as the next argument or after a `=`. Everything after `--` is taken as
+/// the folder.
+pub fn parse<I: IntoIterator<Item = OsString>>(args: I) -> Result<Command, String> {
+    let mut args = args.into_iter();
+    let mut folder = None;
+    let mut sorting = Sorting::Natural;
+    let mut reverse = false;
+    let mut editor = None;
+    let mut config = None;
+    let mut select = None;
+    let mut options_done = false;
+    while let Some(arg) = args.next() {
+        let option = arg
+            .to_str()
+            .filter(|arg| !options_done && arg.starts_with('-') && *arg != "-");
+        let Some(option) = option else {
+            if folder.is_some() {
+                return Err(format!("unexpected argument \"{}\"", arg.to_string_lossy()));
+            }
+            folder = Some(PathBuf::from(arg));
+            continue;
+        };
+        let (name, inline_value) = match option.split_once('=') {
+            Some((name, value)) => (name, Some(OsString::from(value))),
+            None => (option, None),
+        };
+        let mut value = || {
+            inline_value
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("{} needs a value", name))
+        };
+        match name {
+            "--" if inline_value.is_none() => options_done = true,
+            "-h" | "--help" => return Ok(Command::Help),
+            "-V" | "--version" => return Ok(Command::Version),
+            "-r" | "--reverse" if inline_value.is_none() => reverse = true,
+            "-s" | "--sort" => {
+                let value = value()?;
+                let value = value.to_string_lossy();
+                sorting = Sorting::from_name(&value).ok_or_else(|| {
+                    format!(
+                        "{} expects one of {}, not \"{}\"",
+                        name,
+                        Sorting::NAMES.join(", "),
+                        value
+                    )
+                })?;
+            }
+            "-e" | "--editor" => editor = Some(value()?),
+            "-c" | "--config" => config = Some(PathBuf::from(value()?)),
+            "--select" => select = Some(PathBuf::from(value()?)),
+            _ => return Err(format!("unknown option \"{}\"", option)),
+        }
+    }
+    let folder = folder.ok_or("no folder given")?;
+    Ok(Command::Run(Args {
+        folder,
+        sorting,
+        reverse,
+        editor,
+        config,
+        select,
+    }))
+}
+
+/// Exits after explaining what is wrong with the arguments.
+pub fn usage_error<T, S: AsRef<str>>(message: S) -> T {
+    eprintln!(
+        "error: {}\n\n{}\n\nFor more information, try '--help'.",
+        message.as_ref(),
+        USAGE
+    );
+    std::process::exit(2);
+}
+
+/// The absolute path of the note to select at startup. `path` is looked up
+/// relative to the current folder first, then relative to `root`, and has to be
+/// inside `root`, but not in the trash.
+pub fn resolve_select(root: &Path, path: &Path) -> Result<PathBuf, String> {
+    let found: Vec<PathBuf> = [path.to_path_buf(), root.join(path)]
+        .iter()
+        .filter_map(|candidate| absolute(candidate))
+        .collect();
+    if found.is_empty() {
+        return Err(format!("--select: \"{}\" does not exist", path.display()));
+    }
+    let trash_dir = trash::trash_dir(root);
+    found
+        .into_iter()
+        .find(|found| found.starts_with(root) && found != root && !found.starts_with(&trash_dir))
+        .ok_or_else(|| {
+            format!(
+                "--select: \"{}\" is not a note inside {}",
+                path.display(),
+                root.display()
+            )
+        })
+}
+
+/// `path` with its folder made absolute, if it exists. Unlike `canonicalize`,
+/// this keeps a symlink itself instead of its target.
+fn absolute(path: &Path) -> Option<PathBuf> {
+    let name = path.file_name()?;
+    let folder = match path.parent() {
+        Some(folder) if !folder.as_os_str().is_empty() => folder,
+        _ => Path::new("."),
+    };
+    let path = folder.canonicalize().ok()?.join(name);
+    path.symlink_metadata().is_ok().then_some(path)
+}
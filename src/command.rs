@@ -0,0 +1,437 @@
+//! The `:` prompt, which runs commands by name, e.g. `:sort mtime` or `:cd projects`.
+//! Every action can be run this way too, with an optional count, e.g.
+//! `:selection.down 3`.
+
+use std::{
+    io::{Error, ErrorKind, Result},
+    path::{Component, Path, PathBuf},
+};
+
+use chrono::NaiveDate;
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::{
+    actions,
+    input::{InputResult, TextInput},
+    keybindings::MAX_COUNT,
+    sorting::Sorting,
+    state::{updates, Mode, State},
+    trash, util, CrossTerminal,
+};
+
+/// How many commands the history keeps.
+const HISTORY_LENGTH: usize = 100;
+
+/// The argument of `:sort` that reverses the order, besides the sorting names.
+const REVERSE: &str = "reverse";
+const DATE_WORDS: &[&str] = &["today", "tomorrow", "yesterday"];
+const OPTIONS: &[&str] = &["wrap", "nowrap", "wrap!"];
+const QUIT_COMMANDS: &[&str] = &["q", "quit"];
+
+/// What the argument of a command is completed with.
+enum Complete {
+    Words(&'static [&'static str]),
+    /// the names of the sortings and `reverse`
+    Sortings,
+    Paths,
+    Folders,
+}
+
+struct Command {
+    name: &'static str,
+    complete: Complete,
+    /// gets everything after the name, trimmed
+    run: fn(&mut State, &mut CrossTerminal, &str) -> Result<()>,
+}
+
+static COMMANDS: &[Command] = &[
+    Command {
+        name: "sort",
+        complete: Complete::Sortings,
+        run: sort,
+    },
+    Command {
+        name: "rename",
+        complete: Complete::Paths,
+        run: rename,
+    },
+    Command {
+        name: "new",
+        complete: Complete::Paths,
+        run: new,
+    },
+    Command {
+        name: "cd",
+        complete: Complete::Folders,
+        run: cd,
+    },
+    Command {
+        name: "date",
+        complete: Complete::Words(DATE_WORDS),
+        run: date,
+    },
+    Command {
+        name: "set",
+        complete: Complete::Words(OPTIONS),
+        run: set,
+    },
+];
+
+/// The state of the `:` prompt.
+pub struct CommandLine {
+    pub input: TextInput,
+    completion: Option<Completion>,
+    /// the entry of the history being shown, if any
+    history_index: Option<usize>,
+    /// what was typed before going through the history
+    draft: String,
+}
+
+/// The candidates that repeated tabs cycle through.
+struct Completion {
+    /// the text in front of the completed word
+    prefix: String,
+    candidates: Vec<String>,
+    index: usize,
+}
+
+impl CommandLine {
+    pub fn new() -> Self {
+        CommandLine {
+            input: TextInput::new(""),
+            completion: None,
+            history_index: None,
+            draft: String::new(),
+        }
+    }
+
+    /// Which of how many candidates is shown, while there is a choice.
+    pub fn completion_position(&self) -> Option<(usize, usize)> {
+        self.completion
+            .as_ref()
+            .filter(|completion| completion.candidates.len() > 1)
+            .map(|completion| (completion.index + 1, completion.candidates.len()))
+    }
+
+    fn start_completion(&mut self, prefix: String, candidates: Vec<String>) {
+        if candidates.is_empty() {
+            return;
+        }
+        self.input = TextInput::new(prefix.clone() + &candidates[0]);
+        self.completion = Some(Completion {
+            prefix,
+            candidates,
+            index: 0,
+        });
+    }
+
+    /// Shows the next (or previous) candidate. Returns false if there is no choice
+    /// to cycle through, so completion has to start over from the current text.
+    fn cycle_completion(&mut self, forward: bool) -> bool {
+        let Some(completion) = &mut self.completion else {
+            return false;
+        };
+        let len = completion.candidates.len();
+        if len < 2 {
+            return false;
+        }
+        completion.index = if forward {
+            (completion.index + 1) % len
+        } else {
+            (completion.index + len - 1) % len
+        };
+        self.input =
+            TextInput::new(completion.prefix.clone() + &completion.candidates[completion.index]);
+        true
+    }
+
+    fn history_previous(&mut self, history: &[String]) {
+        let index = match self.history_index {
+            Some(0) => return,
+            Some(index) => index - 1,
+            None if history.is_empty() => return,
+            None => {
+                self.draft = self.input.text().to_string();
+                history.len() - 1
+            }
+        };
+        self.history_index = Some(index);
+        self.input = TextInput::new(history[index].as_str());
+        self.completion = None;
+    }
+
+    fn history_next(&mut self, history: &[String]) {
+        let Some(index) = self.history_index else {
+            return;
+        };
+        if index + 1 < history.len() {
+            self.history_index = Some(index + 1);
+            self.input = TextInput::new(history[index + 1].as_str());
+        } else {
+            self.history_index = None;
+            self.input = TextInput::new(std::mem::take(&mut self.draft));
+        }
+        self.completion = None;
+    }
+}
+
+/// Handles a key press at the prompt. Returns whether to quit.
+pub fn handle_key(state: &mut State, terminal: &mut CrossTerminal, key: KeyEvent) -> Result<bool> {
+    let Mode::Command(command) = &mut state.mode else {
+        return Ok(false);
+    };
+    match key.code {
+        KeyCode::Tab | KeyCode::BackTab => {
+            if !command.cycle_completion(key.code == KeyCode::Tab) {
+                let text = command.input.text().to_string();
+                let (start, candidates) = completions(state, &text);
+                if let Mode::Command(command) = &mut state.mode {
+                    command.start_completion(text[..start].to_string(), candidates);
+                }
+            }
+        }
+        KeyCode::Up => command.history_previous(&state.command_history),
+        KeyCode::Down => command.history_next(&state.command_history),
+        KeyCode::Backspace if command.input.text().is_empty() => state.mode = Mode::Normal,
+        _ => match command.input.handle_key(key) {
+            InputResult::Changed => command.completion = None,
+            InputResult::Unchanged => {}
+            InputResult::Cancel => state.mode = Mode::Normal,
+            InputResult::Confirm => {
+                let line = command.input.text().trim().to_string();
+                state.mode = Mode::Normal;
+                if line.is_empty() {
+                    return Ok(false);
+                }
+                state.command_history.retain(|entry| *entry != line);
+                if state.command_history.len() == HISTORY_LENGTH {
+                    state.command_history.remove(0);
+                }
+                state.command_history.push(line.clone());
+                return run(state, terminal, &line);
+            }
+        },
+    }
+    Ok(false)
+}
+
+/// Runs a command line. Returns whether to quit.
+fn run(state: &mut State, terminal: &mut CrossTerminal, line: &str) -> Result<bool> {
+    let (name, arg) = match split(line) {
+        Some((name, arg)) => (name, arg.trim()),
+        None => (line, ""),
+    };
+    if QUIT_COMMANDS.contains(&name) {
+        return Ok(true);
+    }
+    let result = if let Some(command) = COMMANDS.iter().find(|command| command.name == name) {
+        (command.run)(state, terminal, arg)
+    } else if let Some(action) = actions::by_name(name) {
+        match arg {
+            "" => (action.function)(state, terminal, 0),
+            arg => match arg.parse::<usize>() {
+                // like counts typed before a key
+                Ok(count) => (action.function)(state, terminal, count.min(MAX_COUNT)),
+                Err(_) => Err(invalid(format!("invalid count \"{}\"", arg))),
+            },
+        }
+    } else {
+        return Err(invalid(format!("unknown command \"{}\"", name)));
+    };
+    result.map_err(|e| Error::new(e.kind(), format!("{}: {}", name, e)))?;
+    Ok(false)
+}
+
+/// Splits a command line into the name and everything after it, if there is more
+/// than the name.
+fn split(line: &str) -> Option<(&str, &str)> {
+    line.split_once(char::is_whitespace)
+}
+
+/// The candidates for completing the word at the end of `text`, and the byte
+/// index where that word starts.
+fn completions(state: &State, text: &str) -> (usize, Vec<String>) {
+    let Some((name, arg)) = split(text) else {
+        let names = COMMANDS
+            .iter()
+            .map(|command| command.name)
+            .chain(QUIT_COMMANDS.iter().copied())
+            .chain(actions::ACTIONS.iter().map(|action| action.name));
+        return (0, with_prefix(names, text));
+    };
+    let arg = arg.trim_start();
+    let start = text.len() - arg.len();
+    let candidates = match COMMANDS.iter().find(|command| command.name == name) {
+        Some(Command {
+            complete: Complete::Words(words),
+            ..
+        }) => with_prefix(words.iter().copied(), arg),
+        Some(Command {
+            complete: Complete::Sortings,
+            ..
+        }) => with_prefix(Sorting::NAMES.iter().copied().chain([REVERSE]), arg),
+        Some(Command {
+            complete: Complete::Paths,
+            ..
+        }) => paths(state, arg, false),
+        Some(Command {
+            complete: Complete::Folders,
+            ..
+        }) => paths(state, arg, true),
+        None => Vec::new(),
+    };
+    (start, candidates)
+}
+
+fn with_prefix<'a>(words: impl Iterator<Item = &'a str>, prefix: &str) -> Vec<String> {
+    let mut words: Vec<String> = words
+        .filter(|word| word.starts_with(prefix))
+        .map(str::to_string)
+        .collect();
+    words.sort();
+    words.dedup();
+    words
+}
+
+/// The paths starting with `arg`, relative like `arg`, with a slash after folders.
+fn paths(state: &State, arg: &str, folders_only: bool) -> Vec<String> {
+    let (folder, prefix) = match arg.rfind('/') {
+        Some(i) => arg.split_at(i + 1),
+        None => ("", arg),
+    };
+    let Ok(entries) = resolve(state, folder).and_then(std::fs::read_dir) else {
+        return Vec::new();
+    };
+    let trash_dir = trash::trash_dir(&state.root);
+    let mut candidates: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path() != trash_dir)
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let is_folder = entry.file_type().is_ok_and(|file_type| file_type.is_dir());
+            // hidden files only when asked for
+            let hidden = name.starts_with('.') && !prefix.starts_with('.');
+            if !name.starts_with(prefix) || hidden || (folders_only && !is_folder) {
+                return None;
+            }
+            Some(format!(
+                "{}{}{}",
+                folder,
+                name,
+                if is_folder { "/" } else { "" }
+            ))
+        })
+        .collect();
+    candidates.sort_by(|a, b| natord::compare(a, b));
+    candidates
+}
+
+/// The path `arg` refers to: relative to the current folder, or to the notes root
+/// if it starts with a slash. It may not leave the notes root.
+fn resolve(state: &State, arg: &str) -> Result<PathBuf> {
+    let (mut path, relative) = match arg.strip_prefix('/') {
+        Some(relative) => (state.root.clone(), relative),
+        None => (state.cwd.clone(), arg),
+    };
+    for component in Path::new(relative).components() {
+        match component {
+            Component::Normal(name) => path.push(name),
+            Component::ParentDir => {
+                path.pop();
+            }
+            _ => {}
+        }
+    }
+    if !path.starts_with(&state.root) || path.starts_with(trash::trash_dir(&state.root)) {
+        return Err(invalid(format!("\"{}\" is outside of the notes", arg)));
+    }
+    Ok(path)
+}
+
+fn invalid(message: String) -> Error {
+    Error::new(ErrorKind::InvalidInput, message)
+}
+
+fn sort(state: &mut State, terminal: &mut CrossTerminal, arg: &str) -> Result<()> {
+    if arg == REVERSE {
+        return updates::reverse_sort(state, terminal, 0);
+    }
+    let sorting = Sorting::from_name(arg).ok_or_else(|| {
+        invalid(format!(
+            "expected one of {}, {}",
+            Sorting::NAMES.join(", "),
+            REVERSE
+        ))
+    })?;
+    state.set_sorting(sorting);
+    Ok(())
+}
+
+fn rename(state: &mut State, _: &mut CrossTerminal, arg: &str) -> Result<()> {
+    let path = state
+        .selected_file()
+        .map(|file| file.path.clone())
+        .ok_or_else(|| invalid("nothing selected".to_string()))?;
+    state.rename_file(path, arg)
+}
+
+fn new(state: &mut State, terminal: &mut CrossTerminal, arg: &str) -> Result<()> {
+    if arg.is_empty() || arg.ends_with('/') {
+        return Err(invalid("expected a file name".to_string()));
+    }
+    let mut path = resolve(state, arg)?;
+    // notes are markdown, like the ones for dates
+    if path.extension().is_none_or(|extension| extension != "md") {
+        let mut name = path.into_os_string();
+        name.push(".md");
+        path = PathBuf::from(name);
+    }
+    if path.is_dir() {
+        return Err(invalid(format!("\"{}\" is a folder", arg)));
+    }
+    state.edit_note(terminal, path)
+}
+
+fn cd(state: &mut State, _: &mut CrossTerminal, arg: &str) -> Result<()> {
+    let path = match arg {
+        "" => state.root.clone(),
+        arg => resolve(state, arg)?,
+    };
+    if !path.is_dir() {
+        return Err(invalid(format!("\"{}\" is not a folder", arg)));
+    }
+    state.change_dir(path)
+}
+
+/// Opens the note of a date given as `YYYY-MM-DD`, a word like `tomorrow` or a
+/// number of days from today like `-2`.
+fn date(state: &mut State, terminal: &mut CrossTerminal, arg: &str) -> Result<()> {
+    let offset = match arg {
+        "" | "today" => Some(0),
+        "tomorrow" => Some(1),
+        "yesterday" => Some(-1),
+        arg => arg.parse::<i64>().ok(),
+    };
+    let date = match offset {
+        Some(offset) => {
+            util::date_from_today(offset).ok_or_else(|| invalid("date out of range".to_string()))?
+        }
+        None => NaiveDate::parse_from_str(arg, "%Y-%m-%d")
+            .map_err(|_| invalid(format!("expected YYYY-MM-DD, not \"{}\"", arg)))?,
+    };
+    let path = state.cwd.join(format!("{}.md", date.format("%Y-%m-%d")));
+    state.edit_note(terminal, path)
+}
+
+fn set(state: &mut State, terminal: &mut CrossTerminal, arg: &str) -> Result<()> {
+    let wrap = match arg {
+        "wrap" => true,
+        "nowrap" => false,
+        "wrap!" => !state.wrap,
+        _ => return Err(invalid(format!("expected one of {}", OPTIONS.join(", ")))),
+    };
+    if wrap != state.wrap {
+        updates::toggle_wrap(state, terminal, 0)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,161 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+use toml::Spanned;
+
+use crate::{
+    actions,
+    keybindings::{example, parse_key_sequence, KeyBinding},
+};
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct ConfigFile {
+    keys: BTreeMap<Spanned<String>, Spanned<String>>,
+    visual_keys: BTreeMap<Spanned<String>, Spanned<String>>,
+    editor: EditorSection,
+    confirm: BTreeMap<Spanned<String>, bool>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct EditorSection {
+    open_at_line: Option<Spanned<Vec<String>>>,
+}
+
+/// The actions that can ask before doing anything, which are the ones that can
+/// be configured in `[confirm]`.
+const CONFIRMABLE: &[&str] = &["file.delete", "trash.purge"];
+
+pub struct Config {
+    pub key_bindings: Vec<KeyBinding>,
+    /// editor arguments for opening `{file}` at `{line}`, if configured
+    pub open_at_line: Option<Vec<String>>,
+    /// names of the actions that ask before doing anything
+    pub confirm_actions: HashSet<&'static str>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            key_bindings: example::keybindings(),
+            open_at_line: None,
+            confirm_actions: CONFIRMABLE.iter().copied().collect(),
+        }
+    }
+}
+
+/// `$XDG_CONFIG_HOME/notes-tui/config.toml`, falling back to `~/.config`.
+pub fn default_path() -> Option<PathBuf> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| Some(PathBuf::from(std::env::var_os("HOME")?).join(".config")))?;
+    Some(config_home.join("notes-tui").join("config.toml"))
+}
+
+/// Loads the config file at `path`. A missing file yields the default config.
+///
+/// On failure, the error is a report with one `path:line: message` entry per problem.
+pub fn load(path: &Path) -> Result<Config, String> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Config::default()),
+        Err(e) => return Err(format!("{}: {}", path.display(), e)),
+    };
+    let line = |offset: usize| text[..offset].matches('\n').count() + 1;
+
+    let file: ConfigFile = toml::from_str(&text).map_err(|e| match e.span() {
+        Some(span) => format!("{}:{}: {}", path.display(), line(span.start), e.message()),
+        None => format!("{}: {}", path.display(), e.message()),
+    })?;
+
+    let mut entries: Vec<_> = file
+        .keys
+        .into_iter()
+        .map(|entry| (entry, false))
+        .chain(file.visual_keys.into_iter().map(|entry| (entry, true)))
+        .collect();
+    entries.sort_by_key(|((keys, _), _)| keys.span().start);
+
+    let mut errors = Vec::new();
+    let mut config = Config::default();
+    let key_bindings = &mut config.key_bindings;
+    for ((keys, action_name), visual_only) in entries {
+        let binding = parse_key_sequence(keys.get_ref()).and_then(|keys| {
+            let action = actions::by_name(action_name.get_ref())
+                .ok_or_else(|| format!("unknown action `{}`", action_name.get_ref()))?;
+            let binding = KeyBinding::try_new(keys, action)?;
+            Ok(if visual_only {
+                binding.visual_only()
+            } else {
+                binding
+            })
+        });
+        match binding {
+            Ok(binding) => {
+                key_bindings.retain(|other| {
+                    other.keys() != binding.keys() || other.visual_only != binding.visual_only
+                });
+                key_bindings.push(binding);
+            }
+            Err(e) => errors.push(format!(
+                "{}:{}: {}",
+                path.display(),
+                line(keys.span().start),
+                e
+            )),
+        }
+    }
+
+    if let Some(open_at_line) = file.editor.open_at_line {
+        if open_at_line
+            .get_ref()
+            .iter()
+            .any(|arg| arg.contains("{file}"))
+        {
+            config.open_at_line = Some(open_at_line.into_inner());
+        } else {
+            errors.push(format!(
+                "{}:{}: `open_at_line` has to contain `{{file}}`",
+                path.display(),
+                line(open_at_line.span().start)
+            ));
+        }
+    }
+
+    for (action_name, confirm) in file.confirm {
+        let error = match actions::by_name(action_name.get_ref()) {
+            Some(action) if CONFIRMABLE.contains(&action.name) => {
+                if confirm {
+                    config.confirm_actions.insert(action.name);
+                } else {
+                    config.confirm_actions.remove(action.name);
+                }
+                continue;
+            }
+            Some(action) => format!(
+                "`{}` never asks for confirmation, only {} do",
+                action.name,
+                CONFIRMABLE.join(" and ")
+            ),
+            None => format!("unknown action `{}`", action_name.get_ref()),
+        };
+        errors.push(format!(
+            "{}:{}: {}",
+            path.display(),
+            line(action_name.span().start),
+            error
+        ));
+    }
+
+    if errors.is_empty() {
+        Ok(config)
+    } else {
+        Err(errors.join("\n"))
+    }
+}
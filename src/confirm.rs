@@ -0,0 +1,48 @@
+use std::io::Result;
+
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::{
+    state::{Mode, State},
+    CrossTerminal,
+};
+
+pub type OnConfirm = Box<dyn FnOnce(&mut State, &mut CrossTerminal) -> Result<()>>;
+
+/// A modal y/n dialog listing what is about to happen, shown over the mode it was
+/// opened from. That mode is restored before `on_confirm` runs.
+pub struct ConfirmDialog {
+    pub title: String,
+    pub items: Vec<String>,
+    on_confirm: OnConfirm,
+    pub previous: Box<Mode>,
+}
+
+impl ConfirmDialog {
+    pub fn new(title: String, items: Vec<String>, on_confirm: OnConfirm, previous: Mode) -> Self {
+        ConfirmDialog {
+            title,
+            items,
+            on_confirm,
+            previous: Box::new(previous),
+        }
+    }
+}
+
+pub fn handle_key(state: &mut State, terminal: &mut CrossTerminal, key: KeyEvent) -> Result<()> {
+    match key.code {
+        KeyCode::Char('y') | KeyCode::Char('Y') => {
+            if let Mode::Confirm(dialog) = std::mem::replace(&mut state.mode, Mode::Normal) {
+                state.mode = *dialog.previous;
+                (dialog.on_confirm)(state, terminal)?;
+            }
+        }
+        KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Char('q') | KeyCode::Esc => {
+            if let Mode::Confirm(dialog) = std::mem::replace(&mut state.mode, Mode::Normal) {
+                state.mode = *dialog.previous;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
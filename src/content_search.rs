@@ -0,0 +1,163 @@
+use std::{
+    io::Result,
+    path::{Path, PathBuf},
+};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use tui::widgets::ListState;
+
+use crate::{
+    input::InputResult,
+    preview,
+    search::find_match,
+    state::{Mode, State},
+    util, CrossTerminal,
+};
+
+/// snippets longer than this are cut off
+const MAX_SNIPPET_CHARS: usize = 200;
+
+pub struct ContentMatch {
+    pub path: PathBuf,
+    /// 1-based, like editors count
+    pub line: usize,
+    pub snippet: String,
+}
+
+/// The results view, which replaces the file list until it is left with Esc.
+pub struct ContentResults {
+    pub query: String,
+    pub matches: Vec<ContentMatch>,
+    pub list_state: ListState,
+    /// whether the search is still running in the background
+    pub searching: bool,
+}
+
+/// Searches every line of every note below `root` for `query`. Binary and
+/// non-UTF-8 files are skipped, as are files that can't be read.
+pub fn search(root: &Path, query: &str) -> Vec<ContentMatch> {
+    let mut matches = Vec::new();
+    for path in util::walk_files(root) {
+        let bytes = match std::fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(_) => continue,
+        };
+        if util::is_binary(&bytes) {
+            continue;
+        }
+        let text = match String::from_utf8(bytes) {
+            Ok(text) => text,
+            Err(_) => continue,
+        };
+        for (i, line) in text.lines().enumerate() {
+            if find_match(line, query).is_some() {
+                matches.push(ContentMatch {
+                    path: path.clone(),
+                    line: i + 1,
+                    snippet: line.trim().chars().take(MAX_SNIPPET_CHARS).collect(),
+                });
+            }
+        }
+    }
+    matches
+}
+
+pub fn handle_prompt_key(state: &mut State, key: KeyEvent) -> Result<()> {
+    let Mode::ContentSearch(input) = &mut state.mode else {
+        return Ok(());
+    };
+    match input.handle_key(key) {
+        InputResult::Confirm if !input.text().is_empty() => {
+            let query = input.text().to_string();
+            state.mode = Mode::ContentResults(ContentResults {
+                query: query.clone(),
+                matches: Vec::new(),
+                list_state: ListState::default(),
+                searching: true,
+            });
+            update_preview(state);
+            let root = state.root.clone();
+            state.spawner.spawn(move || {
+                let matches = search(&root, &query);
+                Box::new(move |state| show_matches(state, &query, matches))
+            });
+        }
+        InputResult::Confirm | InputResult::Cancel => state.mode = Mode::Normal,
+        InputResult::Changed | InputResult::Unchanged => {}
+    }
+    Ok(())
+}
+
+/// Shows the matches of a finished search, unless the results view has been left
+/// or another search has been started since.
+fn show_matches(state: &mut State, query: &str, matches: Vec<ContentMatch>) -> Result<()> {
+    let Mode::ContentResults(results) = &mut state.mode else {
+        return Ok(());
+    };
+    if !results.searching || results.query != query {
+        return Ok(());
+    }
+    results.searching = false;
+    results
+        .list_state
+        .select(if matches.is_empty() { None } else { Some(0) });
+    results.matches = matches;
+    update_preview(state);
+    Ok(())
+}
+
+pub fn handle_results_key(
+    state: &mut State,
+    terminal: &mut CrossTerminal,
+    key: KeyEvent,
+) -> Result<()> {
+    let Mode::ContentResults(results) = &mut state.mode else {
+        return Ok(());
+    };
+    let len = results.matches.len();
+    let selected = results.list_state.selected();
+    let new_selection = match key.code {
+        KeyCode::Char('j') | KeyCode::Down => selected.map(|i| (i + 1).min(len - 1)),
+        KeyCode::Char('k') | KeyCode::Up => selected.map(|i| i.saturating_sub(1)),
+        KeyCode::Char('g') | KeyCode::Home => selected.map(|_| 0),
+        KeyCode::Char('G') | KeyCode::End => selected.map(|_| len - 1),
+        KeyCode::Enter | KeyCode::Char('l') => return open_selected_match(state, terminal),
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('h') => {
+            state.mode = Mode::Normal;
+            return state.update_file_view_content();
+        }
+        _ => return Ok(()),
+    };
+    results.list_state.select(new_selection);
+    update_preview(state);
+    Ok(())
+}
+
+/// Opens the editor at the selected match.
+pub fn open_selected_match(state: &mut State, terminal: &mut CrossTerminal) -> Result<()> {
+    let Mode::ContentResults(results) = &state.mode else {
+        return Ok(());
+    };
+    if let Some(m) = results.list_state.selected().map(|i| &results.matches[i]) {
+        let (path, line) = (m.path.clone(), m.line);
+        state.open_at_line(terminal, &path, line)?;
+        update_preview(state);
+    }
+    Ok(())
+}
+
+/// Shows the file of the selected match in the file view.
+pub fn update_preview(state: &mut State) {
+    let Mode::ContentResults(results) = &state.mode else {
+        return;
+    };
+    state.file_view_scroll = 0;
+    state.file_view_path = results
+        .list_state
+        .selected()
+        .map(|i| results.matches[i].path.clone());
+    state.file_view_content = match &state.file_view_path {
+        Some(path) => preview::load(path).unwrap_or_default(),
+        None => String::new(),
+    };
+}
@@ -0,0 +1,181 @@
+//! Everything the main loop reacts to arrives through a single channel: terminal
+//! input, ticks, changes on disk and the results of work done in the background.
+//! Only the main loop touches the `State`; other threads send it what to do.
+
+use std::{
+    any::Any,
+    io::{Error, Result},
+    panic::{self, AssertUnwindSafe},
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{channel, Receiver, Sender},
+    },
+    thread,
+    time::Duration,
+};
+
+use crossterm::event;
+use notify::RecommendedWatcher;
+
+use crate::{state::State, watcher};
+
+/// How often a tick is sent.
+const TICK_INTERVAL: Duration = Duration::from_millis(250);
+/// How long the input thread waits for input before checking whether to pause.
+const INPUT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Finishes work done in the background by applying its result to the state.
+pub type Completion = Box<dyn FnOnce(&mut State) -> Result<()> + Send>;
+
+pub enum AppEvent {
+    Input(Result<event::Event>),
+    Tick,
+    FilesChanged,
+    Completed(Completion),
+}
+
+pub struct Events {
+    sender: Sender<AppEvent>,
+    receiver: Receiver<AppEvent>,
+    /// kept alive for as long as changes should be reported
+    _watcher: Option<RecommendedWatcher>,
+}
+
+impl Events {
+    /// Starts reading input, ticking and watching `root` for changes.
+    pub fn new(root: &Path) -> Self {
+        let (sender, receiver) = channel();
+
+        let input_sender = sender.clone();
+        thread::spawn(move || read_input(input_sender));
+
+        let tick_sender = sender.clone();
+        thread::spawn(move || {
+            while tick_sender.send(AppEvent::Tick).is_ok() {
+                thread::sleep(TICK_INTERVAL);
+            }
+        });
+
+        let watcher_sender = sender.clone();
+        let panic_sender = sender.clone();
+        let watcher = watcher::watch(
+            root,
+            move || {
+                let _ = watcher_sender.send(AppEvent::FilesChanged);
+            },
+            move |message| {
+                let _ = panic_sender.send(crashed("watching for changes", message));
+            },
+        )
+        .map_err(|e| simple_log::log::warn!("not watching for changes: {}", e))
+        .ok();
+
+        Events {
+            sender,
+            receiver,
+            _watcher: watcher,
+        }
+    }
+
+    /// Waits for the next event and returns it together with all others that
+    /// have arrived in the meantime, so that they can be handled at once.
+    pub fn next_batch(&self) -> Vec<AppEvent> {
+        // the sender in self keeps the channel open
+        let first = self.receiver.recv().expect("event channel closed");
+        let mut batch = vec![first];
+        batch.extend(self.receiver.try_iter());
+        batch
+    }
+
+    pub fn spawner(&self) -> Spawner {
+        Spawner {
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+/// Runs work on background threads and hands the results to the main loop.
+#[derive(Clone)]
+pub struct Spawner {
+    sender: Sender<AppEvent>,
+}
+
+impl Spawner {
+    /// Runs `work` on a new thread. The completion it returns is run on the main
+    /// thread once it is done.
+    pub fn spawn<F>(&self, work: F)
+    where
+        F: FnOnce() -> Completion + Send + 'static,
+    {
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            let event = match catch_panic(work) {
+                Ok(completion) => AppEvent::Completed(completion),
+                Err(message) => crashed("background work", message),
+            };
+            let _ = sender.send(event);
+        });
+    }
+}
+
+/// Runs `f` and returns the message it panicked with, if it did. Only the main
+/// thread may take the app down, see `terminal::install_panic_hook`.
+pub fn catch_panic<T>(f: impl FnOnce() -> T) -> std::result::Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| panic_message(payload.as_ref()))
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// Reports a panic on another thread as the error of a completion, so that the
+/// main loop shows it in the footer.
+fn crashed(what: &'static str, message: String) -> AppEvent {
+    AppEvent::Completed(Box::new(move |_| {
+        Err(Error::other(format!("{} crashed: {}", what, message)))
+    }))
+}
+
+static INPUT_PAUSED: AtomicBool = AtomicBool::new(false);
+/// whether the input thread is guaranteed not to read input right now
+static INPUT_IDLE: AtomicBool = AtomicBool::new(true);
+
+fn read_input(sender: Sender<AppEvent>) {
+    loop {
+        // announce reading before checking for a pause, see `pause_input`
+        INPUT_IDLE.store(false, Ordering::SeqCst);
+        if INPUT_PAUSED.load(Ordering::SeqCst) {
+            INPUT_IDLE.store(true, Ordering::SeqCst);
+            thread::sleep(INPUT_POLL_INTERVAL);
+            continue;
+        }
+        let input = match event::poll(INPUT_POLL_INTERVAL) {
+            Ok(true) => event::read(),
+            Ok(false) => continue,
+            Err(e) => Err(e),
+        };
+        if sender.send(AppEvent::Input(input)).is_err() {
+            return;
+        }
+    }
+}
+
+/// Stops reading terminal input, so that another program like the editor gets
+/// all of it. Returns once the input thread has stopped.
+pub fn pause_input() {
+    INPUT_PAUSED.store(true, Ordering::SeqCst);
+    while !INPUT_IDLE.load(Ordering::SeqCst) {
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+pub fn resume_input() {
+    INPUT_PAUSED.store(false, Ordering::SeqCst);
+}
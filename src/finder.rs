@@ -0,0 +1,70 @@
+use std::{cmp::Ordering, io::Result, path::PathBuf};
+
+use crossterm::event::KeyEvent;
+
+use crate::{
+    fuzzy_list::{FuzzyCandidate, FuzzyList},
+    input::InputResult,
+    state::{Mode, State},
+    util,
+};
+
+/// The fuzzy finder overlay over all notes below the root, which are given as
+/// paths relative to the root.
+pub type Finder = FuzzyList<String>;
+
+impl FuzzyCandidate for String {
+    fn text(&self) -> &str {
+        self
+    }
+
+    /// Shorter paths first, as they are more likely what is meant.
+    fn tie_break(&self, other: &Self) -> Ordering {
+        self.len().cmp(&other.len()).then(self.cmp(other))
+    }
+}
+
+pub fn new(state: &State) -> Finder {
+    let candidates = util::walk_files(&state.root)
+        .into_iter()
+        .filter_map(|path| {
+            let relative = path.strip_prefix(&state.root).ok()?;
+            Some(relative.to_string_lossy().into_owned())
+        })
+        .collect();
+    FuzzyList::new(candidates)
+}
+
+pub fn handle_key(state: &mut State, key: KeyEvent) -> Result<()> {
+    let Mode::Finder(finder) = &mut state.mode else {
+        return Ok(());
+    };
+    match finder.handle_key(key) {
+        InputResult::Changed | InputResult::Unchanged => {}
+        InputResult::Cancel => state.mode = Mode::Normal,
+        InputResult::Confirm => {
+            let path = finder.selected().map(|p| state.root.join(p));
+            state.mode = Mode::Normal;
+            if let Some(path) = path {
+                return select_path(state, path);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Changes into the folder containing `path` and selects it.
+pub fn select_path(state: &mut State, path: PathBuf) -> Result<()> {
+    let folder = match path.parent() {
+        Some(folder) => folder.to_path_buf(),
+        None => return Ok(()),
+    };
+    if folder != state.cwd {
+        state.change_dir(folder)?;
+    }
+    let index = state.files().iter().position(|f| f.path == path);
+    if index.is_some() {
+        state.select(index)?;
+    }
+    Ok(())
+}
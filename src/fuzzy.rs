@@ -0,0 +1,87 @@
+pub struct FuzzyMatch {
+    pub score: i64,
+    /// char indices of the matched characters in the candidate
+    pub positions: Vec<usize>,
+}
+
+const MATCH_SCORE: i64 = 16;
+const CONSECUTIVE_BONUS: i64 = 8;
+const BOUNDARY_BONUS: i64 = 8;
+const CAMEL_CASE_BONUS: i64 = 4;
+const FILE_NAME_BONUS: i64 = 4;
+
+/// Matches the chars of `query` in order against `candidate`, ignoring case unless
+/// `query` contains a capital. Matches are narrowed down to the shortest window
+/// ending at the first complete match, then scored by how tight they are and
+/// whether they start words. An empty query matches everything with score 0.
+pub fn fuzzy_match(candidate: &str, query: &str) -> Option<FuzzyMatch> {
+    let ignore_case = !query.chars().any(char::is_uppercase);
+    let normalize = |c: char| {
+        if ignore_case {
+            c.to_lowercase().next().unwrap_or(c)
+        } else {
+            c
+        }
+    };
+    let original: Vec<char> = candidate.chars().collect();
+    let chars: Vec<char> = original.iter().copied().map(normalize).collect();
+    let query: Vec<char> = query.chars().map(normalize).collect();
+    if query.is_empty() {
+        return Some(FuzzyMatch {
+            score: 0,
+            positions: Vec::new(),
+        });
+    }
+
+    let mut qi = 0;
+    let mut end = None;
+    for (i, c) in chars.iter().enumerate() {
+        if *c == query[qi] {
+            qi += 1;
+            if qi == query.len() {
+                end = Some(i);
+                break;
+            }
+        }
+    }
+    let end = end?;
+
+    let mut qi = query.len();
+    let mut start = end;
+    for i in (0..=end).rev() {
+        if chars[i] == query[qi - 1] {
+            qi -= 1;
+            if qi == 0 {
+                start = i;
+                break;
+            }
+        }
+    }
+
+    let mut positions = Vec::with_capacity(query.len());
+    for (i, c) in chars.iter().enumerate().take(end + 1).skip(start) {
+        if positions.len() < query.len() && *c == query[positions.len()] {
+            positions.push(i);
+        }
+    }
+
+    let mut score = 0;
+    for (k, &p) in positions.iter().enumerate() {
+        score += MATCH_SCORE;
+        if k > 0 && positions[k - 1] + 1 == p {
+            score += CONSECUTIVE_BONUS;
+        }
+        if p == 0 || matches!(original[p - 1], '/' | '-' | '_' | ' ' | '.') {
+            score += BOUNDARY_BONUS;
+        } else if original[p].is_uppercase() && original[p - 1].is_lowercase() {
+            score += CAMEL_CASE_BONUS;
+        }
+    }
+    let gaps = end + 1 - start - positions.len();
+    score -= gaps as i64;
+    // prefer matches in the file name over matches in the folders leading to it
+    if original[start..].iter().all(|c| *c != '/') {
+        score += FILE_NAME_BONUS;
+    }
+    Some(FuzzyMatch { score, positions })
+}
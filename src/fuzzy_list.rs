@@ -0,0 +1,116 @@
+use std::cmp::Ordering;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use tui::widgets::ListState;
+
+use crate::{
+    fuzzy::{fuzzy_match, FuzzyMatch},
+    input::{InputResult, TextInput},
+};
+
+/// Something a `FuzzyList` can find.
+pub trait FuzzyCandidate {
+    /// the text the query is matched against
+    fn text(&self) -> &str;
+
+    /// Orders candidates with the same score. By default they keep their order.
+    fn tie_break(&self, _other: &Self) -> Ordering {
+        Ordering::Equal
+    }
+}
+
+/// A query with the candidates matching it listed below, best first, like the
+/// finder and the command palette show.
+pub struct FuzzyList<T> {
+    pub input: TextInput,
+    candidates: Vec<T>,
+    /// indices into `candidates` with their matches, best first
+    matches: Vec<(usize, FuzzyMatch)>,
+    pub list_state: ListState,
+}
+
+impl<T: FuzzyCandidate> FuzzyList<T> {
+    pub fn new(candidates: Vec<T>) -> Self {
+        let mut list = FuzzyList {
+            input: TextInput::new(""),
+            candidates,
+            matches: Vec::new(),
+            list_state: ListState::default(),
+        };
+        list.update_matches();
+        list
+    }
+
+    fn update_matches(&mut self) {
+        let query = self.input.text();
+        self.matches = self
+            .candidates
+            .iter()
+            .enumerate()
+            .filter_map(|(i, candidate)| Some((i, fuzzy_match(candidate.text(), query)?)))
+            .collect();
+        let candidates = &self.candidates;
+        // the sort is stable, so ties broken by nothing keep their order
+        self.matches.sort_by(|(i1, m1), (i2, m2)| {
+            m2.score
+                .cmp(&m1.score)
+                .then_with(|| candidates[*i1].tie_break(&candidates[*i2]))
+        });
+        self.list_state.select(if self.matches.is_empty() {
+            None
+        } else {
+            Some(0)
+        });
+    }
+
+    /// The matching candidates, best first, with the char indices of the matched chars.
+    pub fn matches(&self) -> impl Iterator<Item = (&T, &[usize])> {
+        self.matches
+            .iter()
+            .map(|(i, m)| (&self.candidates[*i], m.positions.as_slice()))
+    }
+
+    pub fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    pub fn candidate_count(&self) -> usize {
+        self.candidates.len()
+    }
+
+    fn move_selection(&mut self, down: bool) {
+        if let Some(i) = self.list_state.selected() {
+            let new = if down {
+                (i + 1).min(self.matches.len() - 1)
+            } else {
+                i.saturating_sub(1)
+            };
+            self.list_state.select(Some(new));
+        }
+    }
+
+    pub fn selected(&self) -> Option<&T> {
+        let (i, _) = &self.matches[self.list_state.selected()?];
+        Some(&self.candidates[*i])
+    }
+
+    /// Moves the selection or edits the query. Returns what happened to the query,
+    /// so that the caller can act on Enter and Esc.
+    pub fn handle_key(&mut self, key: KeyEvent) -> InputResult {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Down | KeyCode::Tab => self.move_selection(true),
+            KeyCode::Char('n') if ctrl => self.move_selection(true),
+            KeyCode::Up | KeyCode::BackTab => self.move_selection(false),
+            KeyCode::Char('p') if ctrl => self.move_selection(false),
+            _ => {
+                let result = self.input.handle_key(key);
+                if let InputResult::Changed = result {
+                    self.update_matches();
+                }
+                return result;
+            }
+        }
+        InputResult::Unchanged
+    }
+}
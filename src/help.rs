@@ -0,0 +1,56 @@
+use std::io::Result;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use tui::widgets::ListState;
+
+use crate::{
+    keybindings::KeyBinding,
+    state::{Mode, State},
+};
+
+/// How far `<C-d>` and `<C-u>` move the selection.
+const HALF_PAGE: usize = 10;
+
+/// The cheat sheet overlay listing the key bindings in use.
+pub struct HelpView {
+    pub list_state: ListState,
+}
+
+impl HelpView {
+    pub fn new() -> Self {
+        let mut list_state = ListState::default();
+        list_state.select(Some(0));
+        HelpView { list_state }
+    }
+}
+
+/// The bindings of `state`, grouped by the category of their action.
+pub fn bindings(state: &State) -> Vec<&KeyBinding> {
+    let mut bindings: Vec<&KeyBinding> = state.key_state_machine.key_bindings().iter().collect();
+    bindings.sort_by_key(|binding| binding.action.category);
+    bindings
+}
+
+pub fn handle_key(state: &mut State, key: KeyEvent) -> Result<()> {
+    let last = bindings(state).len().saturating_sub(1);
+    let Mode::Help(help) = &mut state.mode else {
+        return Ok(());
+    };
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    let selected = help.list_state.selected().unwrap_or(0);
+    let new_selection = match key.code {
+        KeyCode::Char('j') | KeyCode::Down => (selected + 1).min(last),
+        KeyCode::Char('k') | KeyCode::Up => selected.saturating_sub(1),
+        KeyCode::Char('d') if ctrl => (selected + HALF_PAGE).min(last),
+        KeyCode::Char('u') if ctrl => selected.saturating_sub(HALF_PAGE),
+        KeyCode::Char('g') | KeyCode::Home => 0,
+        KeyCode::Char('G') | KeyCode::End => last,
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('?') => {
+            state.mode = Mode::Normal;
+            return Ok(());
+        }
+        _ => return Ok(()),
+    };
+    help.list_state.select(Some(new_selection));
+    Ok(())
+}
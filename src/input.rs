@@ -0,0 +1,95 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use unicode_width::UnicodeWidthStr;
+
+pub enum InputResult {
+    Changed,
+    Unchanged,
+    Confirm,
+    Cancel,
+}
+
+/// A single line text field with a cursor, e.g. for prompts in the footer.
+pub struct TextInput {
+    text: String,
+    /// byte index into `text`, always on a char boundary
+    cursor: usize,
+}
+
+impl TextInput {
+    pub fn new<S: Into<String>>(text: S) -> Self {
+        let text = text.into();
+        let cursor = text.len();
+        TextInput { text, cursor }
+    }
+
+    /// `cursor` is a byte index into `text`.
+    pub fn with_cursor<S: Into<String>>(text: S, cursor: usize) -> Self {
+        let text = text.into();
+        assert!(text.is_char_boundary(cursor));
+        TextInput { text, cursor }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The display width of the text in front of the cursor.
+    pub fn cursor_width(&self) -> usize {
+        self.text[..self.cursor].width()
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> InputResult {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Enter => return InputResult::Confirm,
+            KeyCode::Esc => return InputResult::Cancel,
+            KeyCode::Char('a') if ctrl => self.cursor = 0,
+            KeyCode::Char('e') if ctrl => self.cursor = self.text.len(),
+            KeyCode::Char('u') if ctrl => {
+                self.text.replace_range(..self.cursor, "");
+                self.cursor = 0;
+                return InputResult::Changed;
+            }
+            KeyCode::Char('w') if ctrl => {
+                let before = self.text[..self.cursor].trim_end();
+                let start = before
+                    .rfind(char::is_whitespace)
+                    .map(|i| i + 1)
+                    .unwrap_or(0);
+                self.text.replace_range(start..self.cursor, "");
+                self.cursor = start;
+                return InputResult::Changed;
+            }
+            KeyCode::Char(c) if !ctrl && !key.modifiers.contains(KeyModifiers::ALT) => {
+                self.text.insert(self.cursor, c);
+                self.cursor += c.len_utf8();
+                return InputResult::Changed;
+            }
+            KeyCode::Backspace => {
+                if let Some(c) = self.text[..self.cursor].chars().next_back() {
+                    self.cursor -= c.len_utf8();
+                    self.text.remove(self.cursor);
+                    return InputResult::Changed;
+                }
+            }
+            KeyCode::Delete if self.cursor < self.text.len() => {
+                self.text.remove(self.cursor);
+                return InputResult::Changed;
+            }
+            KeyCode::Left => {
+                if let Some(c) = self.text[..self.cursor].chars().next_back() {
+                    self.cursor -= c.len_utf8();
+                }
+            }
+            KeyCode::Right => {
+                if let Some(c) = self.text[self.cursor..].chars().next() {
+                    self.cursor += c.len_utf8();
+                }
+            }
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.text.len(),
+            _ => {}
+        }
+        InputResult::Unchanged
+    }
+}
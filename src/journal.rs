@@ -0,0 +1,233 @@
+//! A journal of the changes the app made on disk, so they can be undone and
+//! redone. Every change remembers what the file looked like right after it, and
+//! is only reverted if the file still looks like that.
+
+use std::{
+    io::{Error, ErrorKind, Result},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::trash::{self, TrashEntry};
+
+/// Modification time and size, enough to notice that a file has been changed.
+type Fingerprint = Option<(SystemTime, u64)>;
+
+fn fingerprint(path: &Path) -> Fingerprint {
+    let metadata = path.symlink_metadata().ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+enum ChangeKind {
+    Trashed(TrashEntry),
+    Restored(PathBuf),
+    Renamed { from: PathBuf, to: PathBuf },
+    Created(PathBuf),
+}
+
+/// One change on disk, together with the fingerprint of the file it left behind.
+pub struct Change {
+    kind: ChangeKind,
+    fingerprint: Fingerprint,
+}
+
+impl Change {
+    fn new(kind: ChangeKind) -> Self {
+        let fingerprint = fingerprint(Self::result_path(&kind));
+        Change { kind, fingerprint }
+    }
+
+    pub fn trashed(entry: TrashEntry) -> Self {
+        Self::new(ChangeKind::Trashed(entry))
+    }
+
+    pub fn restored(path: PathBuf) -> Self {
+        Self::new(ChangeKind::Restored(path))
+    }
+
+    pub fn renamed(from: PathBuf, to: PathBuf) -> Self {
+        Self::new(ChangeKind::Renamed { from, to })
+    }
+
+    pub fn created(path: PathBuf) -> Self {
+        Self::new(ChangeKind::Created(path))
+    }
+
+    /// Where the file ended up.
+    fn result_path(kind: &ChangeKind) -> &Path {
+        match kind {
+            ChangeKind::Trashed(entry) => &entry.path,
+            ChangeKind::Restored(path) | ChangeKind::Created(path) => path,
+            ChangeKind::Renamed { to, .. } => to,
+        }
+    }
+
+    /// Where the file would end up if the change was reverted, unless that is the trash.
+    fn revert_target(&self) -> Option<&Path> {
+        match &self.kind {
+            ChangeKind::Trashed(entry) => Some(&entry.original_path),
+            ChangeKind::Renamed { from, .. } => Some(from),
+            ChangeKind::Restored(_) | ChangeKind::Created(_) => None,
+        }
+    }
+
+    /// Makes sure reverting neither loses a later modification nor overwrites anything.
+    fn check_revert(&self) -> Result<()> {
+        let path = Self::result_path(&self.kind);
+        let current = fingerprint(path);
+        if current.is_none() {
+            return Err(Error::new(
+                ErrorKind::NotFound,
+                format!("{} no longer exists", path.display()),
+            ));
+        }
+        if current != self.fingerprint {
+            return Err(Error::other(format!(
+                "{} has changed since",
+                path.display()
+            )));
+        }
+        match self.revert_target() {
+            Some(target) if target.symlink_metadata().is_ok() => Err(Error::new(
+                ErrorKind::AlreadyExists,
+                format!("{} already exists", target.display()),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Reverts the change and returns the change that did that.
+    fn revert(&self, root: &Path) -> Result<Change> {
+        Ok(match &self.kind {
+            ChangeKind::Trashed(entry) => {
+                trash::restore(entry)?;
+                Change::restored(entry.original_path.clone())
+            }
+            ChangeKind::Restored(path) | ChangeKind::Created(path) => {
+                Change::trashed(trash::trash(root, path)?)
+            }
+            ChangeKind::Renamed { from, to } => {
+                std::fs::rename(to, from)?;
+                Change::renamed(to.clone(), from.clone())
+            }
+        })
+    }
+
+    fn describe(&self, root: &Path) -> String {
+        let relative = |path: &Path| {
+            let path = path.strip_prefix(root).unwrap_or(path);
+            path.display().to_string()
+        };
+        match &self.kind {
+            ChangeKind::Trashed(entry) => {
+                format!("move {} to the trash", relative(&entry.original_path))
+            }
+            ChangeKind::Restored(path) => format!("restore {}", relative(path)),
+            ChangeKind::Renamed { from, to } => {
+                format!("rename {} to {}", relative(from), relative(to))
+            }
+            ChangeKind::Created(path) => format!("create {}", relative(path)),
+        }
+    }
+}
+
+/// The changes made by a single action, e.g. deleting all files of a visual range.
+pub type Operation = Vec<Change>;
+
+#[derive(Default)]
+pub struct Journal {
+    undo: Vec<Operation>,
+    redo: Vec<Operation>,
+}
+
+impl Journal {
+    /// Records an operation done by the user, which makes everything undone so far
+    /// impossible to redo.
+    pub fn record(&mut self, operation: Operation) {
+        if !operation.is_empty() {
+            self.undo.push(operation);
+            self.redo.clear();
+        }
+    }
+
+    /// Reverts the last operation and returns what it was.
+    pub fn undo(&mut self, root: &Path) -> Result<String> {
+        let mut operation = self
+            .undo
+            .pop()
+            .ok_or_else(|| Error::new(ErrorKind::NotFound, "already at oldest change"))?;
+        let description = describe(&operation, root);
+        let total = operation.len();
+        let (reverted, result) = revert(&mut operation, root);
+        let done = reverted.len();
+        push_unless_empty(&mut self.undo, operation);
+        push_unless_empty(&mut self.redo, reverted);
+        result
+            .map(|()| description)
+            .map_err(|e| partly_done(e, done, total, "undone"))
+    }
+
+    /// Reverts the last undo and returns what was done again.
+    pub fn redo(&mut self, root: &Path) -> Result<String> {
+        let mut operation = self
+            .redo
+            .pop()
+            .ok_or_else(|| Error::new(ErrorKind::NotFound, "already at newest change"))?;
+        let total = operation.len();
+        let (reverted, result) = revert(&mut operation, root);
+        let description = describe(&reverted, root);
+        let done = reverted.len();
+        push_unless_empty(&mut self.redo, operation);
+        push_unless_empty(&mut self.undo, reverted);
+        result
+            .map(|()| description)
+            .map_err(|e| partly_done(e, done, total, "redone"))
+    }
+}
+
+/// Reverts the changes of `operation`, last first, after checking that all of
+/// them can be, and returns the operation that did that. If a change fails
+/// anyway, the ones before it are left alone: they stay in `operation`, which
+/// then only holds what is still to be reverted, so that both operations keep
+/// matching what is on disk.
+fn revert(operation: &mut Operation, root: &Path) -> (Operation, Result<()>) {
+    if let Err(e) = operation.iter().try_for_each(Change::check_revert) {
+        return (Vec::new(), Err(e));
+    }
+    let mut reverted = Vec::with_capacity(operation.len());
+    while let Some(change) = operation.pop() {
+        match change.revert(root) {
+            Ok(change) => reverted.push(change),
+            Err(e) => {
+                operation.push(change);
+                return (reverted, Err(e));
+            }
+        }
+    }
+    (reverted, Ok(()))
+}
+
+fn push_unless_empty(operations: &mut Vec<Operation>, operation: Operation) {
+    if !operation.is_empty() {
+        operations.push(operation);
+    }
+}
+
+/// Adds to `e` how much of an operation got done before it happened.
+fn partly_done(e: Error, done: usize, total: usize, what: &str) -> Error {
+    if done == 0 {
+        return e;
+    }
+    Error::new(
+        e.kind(),
+        format!("{}, after {} of {} changes were {}", e, done, total, what),
+    )
+}
+
+fn describe(operation: &Operation, root: &Path) -> String {
+    match operation.as_slice() {
+        [] => String::new(),
+        [change] => change.describe(root),
+        [change, rest @ ..] => format!("{} and {} more", change.describe(root), rest.len()),
+    }
+}
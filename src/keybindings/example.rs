@@ -1,34 +1,104 @@
 use crossterm::event::{KeyCode, KeyModifiers};
 
 use super::{KeyBinding, KeyBindingPart};
-use crate::state::updates::*;
 
 pub fn keybindings() -> Vec<KeyBinding> {
     vec![
-        KeyBinding::new_from_chars("l", false, open_selected),
-        KeyBinding::new_from_chars("j", true, selection_down),
-        KeyBinding::new_from_chars("k", true, selection_up),
-        KeyBinding::new_from_chars("o", true, open_rel_date_fwd),
-        KeyBinding::new_from_chars("b", true, open_rel_date_bwd),
-        KeyBinding::new_from_chars("gg", true, selection_top),
-        KeyBinding::new_from_chars("sn", false, sort_by_natural),
-        KeyBinding::new_from_chars("ss", false, sort_by_size),
-        KeyBinding::new_from_chars("sc", false, sort_by_ctime),
-        KeyBinding::new_from_chars("sm", false, sort_by_mtime),
-        KeyBinding::new_from_chars("sa", false, sort_by_name),
-        KeyBinding::new_from_chars("sr", false, reverse_sort),
-        KeyBinding::new_from_chars("dd", true, delete_file),
+        KeyBinding::new_from_chars("l", "file.open"),
+        KeyBinding::new(
+            vec![KeyBindingPart::new(KeyCode::Enter, KeyModifiers::NONE)],
+            "file.open",
+        ),
+        KeyBinding::new_from_chars("h", "folder.parent"),
+        KeyBinding::new_from_chars("j", "selection.down"),
+        KeyBinding::new_from_chars("k", "selection.up"),
+        KeyBinding::new_from_chars("o", "date.forward"),
+        KeyBinding::new_from_chars("b", "date.backward"),
+        KeyBinding::new_from_chars("gg", "selection.top"),
+        KeyBinding::new_from_chars("sn", "sort.natural"),
+        KeyBinding::new_from_chars("ss", "sort.size"),
+        KeyBinding::new_from_chars("sc", "sort.ctime"),
+        KeyBinding::new_from_chars("sm", "sort.mtime"),
+        KeyBinding::new_from_chars("sa", "sort.name"),
+        KeyBinding::new_from_chars("sr", "sort.reverse"),
+        KeyBinding::new_from_chars("dd", "file.delete"),
+        KeyBinding::new_from_chars("yy", "file.yank"),
+        KeyBinding::new_from_chars("a", "file.rename"),
+        KeyBinding::new_from_chars("A", "file.rename_end"),
+        KeyBinding::new_from_chars("u", "file.undo"),
+        KeyBinding::new(
+            vec![KeyBindingPart::new(
+                KeyCode::Char('r'),
+                KeyModifiers::CONTROL,
+            )],
+            "file.redo",
+        ),
+        KeyBinding::new_from_chars("v", "selection.visual"),
+        KeyBinding::new_from_chars("T", "trash.open"),
+        KeyBinding::new_from_chars("r", "trash.restore"),
+        KeyBinding::new_from_chars("x", "trash.purge"),
+        KeyBinding::new_from_chars("D", "trash.purge"),
+        KeyBinding::new_from_chars("w", "preview.wrap"),
+        KeyBinding::new_from_chars("d", "file.delete").visual_only(),
+        KeyBinding::new_from_chars("y", "file.yank").visual_only(),
+        KeyBinding::new_from_chars(":", "command"),
+        KeyBinding::new_from_chars("?", "help"),
+        KeyBinding::new_from_chars("M", "messages"),
+        KeyBinding::new_from_chars("/", "search.start"),
+        KeyBinding::new_from_chars("n", "search.next"),
+        KeyBinding::new_from_chars("N", "search.previous"),
+        KeyBinding::new(
+            vec![KeyBindingPart::new(
+                KeyCode::Char('f'),
+                KeyModifiers::CONTROL,
+            )],
+            "search.content",
+        ),
+        KeyBinding::new(
+            vec![KeyBindingPart::new(
+                KeyCode::Char('t'),
+                KeyModifiers::CONTROL,
+            )],
+            "search.fuzzy",
+        ),
+        KeyBinding::new(
+            vec![KeyBindingPart::new(
+                KeyCode::Char('p'),
+                KeyModifiers::CONTROL,
+            )],
+            "palette",
+        ),
+        KeyBinding::new(
+            vec![KeyBindingPart::new(
+                KeyCode::Char('e'),
+                KeyModifiers::CONTROL,
+            )],
+            "preview.down",
+        ),
+        KeyBinding::new(
+            vec![KeyBindingPart::new(
+                KeyCode::Char('y'),
+                KeyModifiers::CONTROL,
+            )],
+            "preview.up",
+        ),
+        KeyBinding::new(
+            vec![KeyBindingPart::new(
+                KeyCode::Char('d'),
+                KeyModifiers::CONTROL,
+            )],
+            "preview.half_page_down",
+        ),
+        KeyBinding::new(
+            vec![KeyBindingPart::new(
+                KeyCode::Char('u'),
+                KeyModifiers::CONTROL,
+            )],
+            "preview.half_page_up",
+        ),
         KeyBinding::new(
             vec![KeyBindingPart::new(KeyCode::Char('G'), KeyModifiers::SHIFT)],
-            true,
-            selection_bottom,
-        ),
-        KeyBinding::new(vec![
-            KeyBindingPart::new(KeyCode::Enter, KeyModifiers::NONE),
-            KeyBindingPart::new(KeyCode::Backspace, KeyModifiers::NONE),
-            KeyBindingPart::new(KeyCode::Char('t'), KeyModifiers::CONTROL | KeyModifiers::ALT),
-            KeyBindingPart::new(KeyCode::Char('n'), KeyModifiers::CONTROL),
-            KeyBindingPart::new(KeyCode::Tab, KeyModifiers::NONE),
-        ], false, |_,_,_| Ok(())),
+            "selection.bottom",
+        ),
     ]
 }
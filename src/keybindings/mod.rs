@@ -1,9 +1,21 @@
-use crate::{state::*, CrossTerminal};
+use crate::{
+    actions::{self, NamedAction},
+    state::*,
+    CrossTerminal,
+};
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
 pub mod example;
 mod serde;
 
+pub use self::serde::parse_key_sequence;
+
+pub type Action =
+    fn(state: &mut State, terminal: &mut CrossTerminal, count: usize) -> std::io::Result<()>;
+
+/// Counts stop growing once they are larger than this.
+pub const MAX_COUNT: usize = 429000000;
+
 #[derive(PartialEq, Eq, Clone, Copy)]
 pub struct KeyBindingPart {
     code: KeyCode,
@@ -13,9 +25,9 @@ pub struct KeyBindingPart {
 #[derive(Clone)]
 pub struct KeyBinding {
     keys: Vec<KeyBindingPart>,
-    repeatable: bool,
-    pub action:
-        fn(state: &mut State, terminal: &mut CrossTerminal, count: usize) -> std::io::Result<()>,
+    pub action: &'static NamedAction,
+    /// only active in visual mode, where it takes precedence over other bindings
+    pub visual_only: bool,
 }
 
 pub struct KeyStateMachine {
@@ -33,46 +45,56 @@ impl KeyBindingPart {
     }
 
     fn new_char(c: char) -> KeyBindingPart {
-        Self::new(KeyCode::Char(c), KeyModifiers::NONE)
+        // crossterm reports capitals together with shift
+        if c.is_uppercase() {
+            Self::new(KeyCode::Char(c), KeyModifiers::SHIFT)
+        } else {
+            Self::new(KeyCode::Char(c), KeyModifiers::NONE)
+        }
     }
 }
 
 impl KeyBinding {
-    fn new(
+    pub fn try_new(
         keys: Vec<KeyBindingPart>,
-        repeatable: bool,
-        action: fn(
-            state: &mut State,
-            terminal: &mut CrossTerminal,
-            count: usize,
-        ) -> std::io::Result<()>,
-    ) -> Self {
-        assert!(!keys.is_empty());
+        action: &'static NamedAction,
+    ) -> Result<Self, String> {
+        if keys.is_empty() {
+            return Err("empty key sequence".to_string());
+        }
         if let KeyCode::Char(c) = keys[0].code {
-            assert!(!c.is_digit(10));
+            if c.is_ascii_digit() {
+                return Err(format!("key sequence can't start with the digit `{}`", c));
+            }
         }
-        Self {
+        Ok(Self {
             keys,
-            repeatable,
             action,
-        }
+            visual_only: false,
+        })
+    }
+
+    pub fn visual_only(mut self) -> Self {
+        self.visual_only = true;
+        self
     }
 
-    fn new_from_chars<S: Into<String>>(
-        chars: S,
-        repeatable: bool,
-        action: fn(
-            state: &mut State,
-            terminal: &mut CrossTerminal,
-            count: usize,
-        ) -> std::io::Result<()>,
-    ) -> Self {
+    fn new(keys: Vec<KeyBindingPart>, action_name: &str) -> Self {
+        let action = actions::by_name(action_name)
+            .unwrap_or_else(|| panic!("unknown action `{}`", action_name));
+        Self::try_new(keys, action).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn new_from_chars<S: Into<String>>(chars: S, action_name: &str) -> Self {
         Self::new(
             chars.into().chars().map(KeyBindingPart::new_char).collect(),
-            repeatable,
-            action,
+            action_name,
         )
     }
+
+    pub fn keys(&self) -> &[KeyBindingPart] {
+        &self.keys
+    }
 }
 
 impl KeyStateMachine {
@@ -89,7 +111,7 @@ impl KeyStateMachine {
     }
 
     fn count_digit(&mut self, d: u32) {
-        if self.current_count > 429000000 {
+        if self.current_count > MAX_COUNT {
             return;
         }
         if self.current_count == 0 && d == 0 {
@@ -98,11 +120,11 @@ impl KeyStateMachine {
         self.current_count = self.current_count * 10 + d as usize;
     }
 
-    pub fn register_event(&mut self, e: KeyEvent) -> Option<KeyBinding> {
+    pub fn register_event(&mut self, e: KeyEvent, visual: bool) -> Option<KeyBinding> {
         if self.is_done {
             self.reset();
         }
-        
+
         let key_binding_part = KeyBindingPart {
             code: e.code,
             modifiers: e.modifiers,
@@ -121,13 +143,14 @@ impl KeyStateMachine {
         if self.key_count_after_number == 0 && self.current_count > 1 {
             self.current_bindings.retain(|binding_index| {
                 let binding = &self.key_bindings[*binding_index];
-                binding.repeatable
+                binding.action.takes_count
             });
         }
 
         self.current_bindings.retain(|binding_index| {
             let binding = &self.key_bindings[*binding_index];
             binding.keys[self.key_count_after_number] == key_binding_part
+                && (visual || !binding.visual_only)
         });
 
         if self.current_bindings.is_empty() {
@@ -138,14 +161,17 @@ impl KeyStateMachine {
         self.current_keys.push(key_binding_part);
         self.key_count_after_number += 1;
 
-        for binding_index in self.current_bindings.iter() {
-            let binding = self.key_bindings[*binding_index].clone();
-            if self.key_count_after_number == binding.keys.len() {
-                self.is_done = true;
-                return Some(binding);
-            }
+        let completed = self
+            .current_bindings
+            .iter()
+            .map(|binding_index| &self.key_bindings[*binding_index])
+            .filter(|binding| self.key_count_after_number == binding.keys.len())
+            .max_by_key(|binding| binding.visual_only)
+            .cloned();
+        if completed.is_some() {
+            self.is_done = true;
         }
-        None
+        completed
     }
 
     pub fn reset(&mut self) {
@@ -159,4 +185,35 @@ impl KeyStateMachine {
     pub fn count(&self) -> usize {
         self.current_count
     }
+
+    pub fn key_bindings(&self) -> &[KeyBinding] {
+        &self.key_bindings
+    }
+
+    /// The actions bound to `key` alone outside of visual mode, for views that
+    /// run a few actions of their own, like the trash.
+    pub fn single_key_actions(
+        &self,
+        key: KeyEvent,
+    ) -> impl Iterator<Item = &'static NamedAction> + '_ {
+        let part = KeyBindingPart::new(key.code, key.modifiers);
+        self.key_bindings
+            .iter()
+            .filter(move |binding| !binding.visual_only && binding.keys == [part])
+            .map(|binding| binding.action)
+    }
+
+    /// The bindings that can still complete the keys typed so far, with the keys
+    /// that each needs next. Empty unless a sequence has been started.
+    pub fn pending_bindings(&self) -> Vec<(&[KeyBindingPart], &KeyBinding)> {
+        if self.is_done || self.key_count_after_number == 0 {
+            return Vec::new();
+        }
+        self.current_bindings
+            .iter()
+            .map(|binding_index| &self.key_bindings[*binding_index])
+            .filter(|binding| binding.keys.len() > self.key_count_after_number)
+            .map(|binding| (&binding.keys[self.key_count_after_number..], binding))
+            .collect()
+    }
 }
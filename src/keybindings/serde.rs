@@ -6,6 +6,44 @@ use super::KeyBindingPart;
 
 struct DisplayableKeyCode(KeyCode);
 
+const MOD_MAPPING: [(KeyModifiers, char); 6] = [
+    (KeyModifiers::ALT, 'A'),
+    (KeyModifiers::CONTROL, 'C'),
+    (KeyModifiers::HYPER, 'H'),
+    (KeyModifiers::META, 'M'),
+    (KeyModifiers::SHIFT, 'S'),
+    (KeyModifiers::SUPER, 'W'),
+];
+
+const NAMED_KEYS: [(&str, KeyCode); 26] = [
+    ("Backspace", KeyCode::Backspace),
+    ("BS", KeyCode::Backspace),
+    ("Enter", KeyCode::Enter),
+    ("CR", KeyCode::Enter),
+    ("Left", KeyCode::Left),
+    ("Right", KeyCode::Right),
+    ("Up", KeyCode::Up),
+    ("Down", KeyCode::Down),
+    ("Home", KeyCode::Home),
+    ("End", KeyCode::End),
+    ("PageUp", KeyCode::PageUp),
+    ("PageDown", KeyCode::PageDown),
+    ("Tab", KeyCode::Tab),
+    ("BackTab", KeyCode::BackTab),
+    ("Delete", KeyCode::Delete),
+    ("Insert", KeyCode::Insert),
+    ("Null", KeyCode::Null),
+    ("Esc", KeyCode::Esc),
+    ("CapsLock", KeyCode::CapsLock),
+    ("ScrollLock", KeyCode::ScrollLock),
+    ("NumLock", KeyCode::NumLock),
+    ("PrintScreen", KeyCode::PrintScreen),
+    ("Pause", KeyCode::Pause),
+    ("Menu", KeyCode::Menu),
+    ("Space", KeyCode::Char(' ')),
+    ("lt", KeyCode::Char('<')),
+];
+
 impl Display for DisplayableKeyCode {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self.0 {
@@ -26,18 +64,10 @@ impl Display for KeyBindingPart {
                 return write!(f, "{}", c);
             }
         }
-        let mod_mapping = [
-            (KeyModifiers::ALT, 'A'),
-            (KeyModifiers::CONTROL, 'C'),
-            (KeyModifiers::HYPER, 'H'),
-            (KeyModifiers::META, 'M'),
-            (KeyModifiers::SHIFT, 'S'),
-            (KeyModifiers::SUPER, 'W'),
-        ];
 
         let mut contained_mods = String::new();
 
-        for (km, letter) in mod_mapping {
+        for (km, letter) in MOD_MAPPING {
             if self.modifiers.bits() & km.bits() > 0 {
                 contained_mods.push(letter);
             }
@@ -57,3 +87,100 @@ impl Display for KeyBindingPart {
         }
     }
 }
+
+/// Parses a key sequence like `gg`, `<C-d>` or `<A-S-x>` into its parts.
+///
+/// This is the inverse of the `Display` impl of `KeyBindingPart`. Modifiers may
+/// be written either concatenated (`<AC-t>`) or separated by dashes (`<A-C-t>`).
+/// A `<` that doesn't start a valid key is taken literally.
+pub fn parse_key_sequence(s: &str) -> Result<Vec<KeyBindingPart>, String> {
+    let mut parts = Vec::new();
+    let mut rest = s;
+    while let Some(c) = rest.chars().next() {
+        if c == '<' {
+            // try every closing bracket so that e.g. `<C->>` works
+            let mut error = None;
+            let mut parsed = None;
+            for (end, _) in rest.match_indices('>').filter(|(i, _)| *i >= 2) {
+                match parse_bracketed(&rest[1..end]) {
+                    Ok(part) => {
+                        parsed = Some((part, end));
+                        break;
+                    }
+                    Err(e) => {
+                        error.get_or_insert(e);
+                    }
+                }
+            }
+            if let Some((part, end)) = parsed {
+                parts.push(part);
+                rest = &rest[end + 1..];
+                continue;
+            }
+            if let Some(e) = error {
+                return Err(e);
+            }
+        }
+        parts.push(char_part(c, KeyModifiers::NONE)?);
+        rest = &rest[c.len_utf8()..];
+    }
+    if parts.is_empty() {
+        return Err("empty key sequence".to_string());
+    }
+    Ok(parts)
+}
+
+fn parse_bracketed(content: &str) -> Result<KeyBindingPart, String> {
+    let (mods, code) = if let Some(mods) = content.strip_suffix("--") {
+        (mods, "-")
+    } else if let Some(i) = content.rfind('-').filter(|i| *i > 0) {
+        (&content[..i], &content[i + 1..])
+    } else {
+        ("", content)
+    };
+
+    let mut modifiers = KeyModifiers::NONE;
+    for letter in mods.chars().filter(|c| *c != '-') {
+        let (km, _) = MOD_MAPPING
+            .iter()
+            .find(|(_, l)| *l == letter.to_ascii_uppercase())
+            .ok_or_else(|| format!("unknown modifier `{}` in `<{}>`", letter, content))?;
+        modifiers |= *km;
+    }
+
+    let mut chars = code.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return char_part(c, modifiers);
+    }
+    if let Some(n) = code.strip_prefix('F').and_then(|n| n.parse().ok()) {
+        return Ok(KeyBindingPart::new(KeyCode::F(n), modifiers));
+    }
+    match NAMED_KEYS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(code))
+    {
+        Some((_, KeyCode::Char(c))) => char_part(*c, modifiers),
+        Some((_, code)) => Ok(KeyBindingPart::new(*code, modifiers)),
+        None => Err(format!("unknown key `{}` in `<{}>`", code, content)),
+    }
+}
+
+/// Builds a char key the way crossterm reports it: capitals always come with shift.
+fn char_part(c: char, modifiers: KeyModifiers) -> Result<KeyBindingPart, String> {
+    if modifiers.contains(KeyModifiers::SHIFT) {
+        let mut upper = c.to_uppercase();
+        return match (upper.next(), upper.next()) {
+            (Some(u), None) if u.is_uppercase() => {
+                Ok(KeyBindingPart::new(KeyCode::Char(u), modifiers))
+            }
+            _ => Err(format!("shift can't be combined with `{}`", c)),
+        };
+    }
+    if c.is_uppercase() {
+        return Ok(KeyBindingPart::new(
+            KeyCode::Char(c),
+            modifiers | KeyModifiers::SHIFT,
+        ));
+    }
+    Ok(KeyBindingPart::new(KeyCode::Char(c), modifiers))
+}
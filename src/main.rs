@@ -1,91 +1,216 @@
-use crossterm::{
-    event::{self, Event, KeyCode},
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-};
+use confirm::ConfirmDialog;
+use crossterm::event::{Event, KeyCode, KeyEvent};
+use events::{AppEvent, Events};
+use finder::Finder;
+use fuzzy_list::{FuzzyCandidate, FuzzyList};
+use help::HelpView;
+use input::TextInput;
 use keybindings::KeyBindingPart;
-use sorting::Sorting;
-use state::State;
-use std::{
-    io::{self, Stdout},
-    path::PathBuf,
-};
+use messages::{MessageHistory, Severity};
+use mouse::Mouse;
+use palette::Palette;
+use state::{Mode, State};
+use std::io::{self, Stdout};
+use terminal::TerminalGuard;
 use tui::{
     backend::{Backend, CrosstermBackend},
-    layout::{Constraint, Direction, Layout},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    text::{Span, Spans, Text},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
     Frame, Terminal,
 };
+use unicode_width::UnicodeWidthStr;
 use util::fail;
 
+mod actions;
+mod cli;
+mod command;
+mod config;
+mod confirm;
+mod content_search;
+mod events;
+mod finder;
+mod fuzzy;
+mod fuzzy_list;
+mod help;
+mod input;
+mod journal;
 mod keybindings;
+mod markdown;
+mod messages;
+mod mouse;
+mod palette;
+mod preview;
+mod rename;
+mod search;
 mod sorting;
 mod state;
+mod terminal;
+mod trash;
 mod util;
+mod watcher;
+mod wrap;
 
 type CrossTerminal = Terminal<CrosstermBackend<Stdout>>;
 
 fn main() -> io::Result<()> {
     util::init_logging()?;
 
-    let folder_path: PathBuf = std::env::args()
-        .nth(1)
-        .unwrap_or_else(|| fail("no folder given"))
-        .into();
-    if !folder_path.is_dir() {
-        fail::<(), String>(format!(
-            "path {} is not a directory",
-            folder_path.clone().into_os_string().into_string().unwrap()
-        ));
+    let args = match cli::parse(std::env::args_os().skip(1)) {
+        Ok(cli::Command::Run(args)) => args,
+        Ok(cli::Command::Help) => {
+            print!("{}", cli::HELP);
+            return Ok(());
+        }
+        Ok(cli::Command::Version) => {
+            println!("notes-tui {}", env!("CARGO_PKG_VERSION"));
+            return Ok(());
+        }
+        Err(e) => cli::usage_error(e),
+    };
+    if !args.folder.is_dir() {
+        cli::usage_error::<(), String>(format!("\"{}\" is not a folder", args.folder.display()));
     }
+    // the root has to be absolute so that going up to it always works
+    let folder_path = args.folder.canonicalize()?;
+    let select = args
+        .select
+        .map(|path| cli::resolve_select(&folder_path, &path).unwrap_or_else(cli::usage_error));
 
-    // setup terminal
-    enable_raw_mode()?;
-    execute!(std::io::stdout(), EnterAlternateScreen)?;
-    let backend = CrosstermBackend::new(std::io::stdout());
-    let terminal = Terminal::new(backend)?;
+    let config_path = match args.config {
+        Some(path) if !path.is_file() => {
+            cli::usage_error(format!("config file \"{}\" does not exist", path.display()))
+        }
+        Some(path) => path,
+        None => config::default_path().unwrap_or_else(|| fail("could not find config directory")),
+    };
+    let config = config::load(&config_path).unwrap_or_else(fail);
 
-    let editor = std::env::var_os("VISUAL")
+    let editor = args
+        .editor
+        .or_else(|| std::env::var_os("VISUAL"))
         .or_else(|| std::env::var_os("EDITOR"))
-        .unwrap_or_else(|| fail("could not find $VISUAL or $EDITOR"));
-
-    let mut state = State::new(folder_path, editor, Sorting::Natural, false);
-    state.update_files()?;
+        .unwrap_or_else(|| {
+            fail("could not find an editor, set $VISUAL or $EDITOR or use --editor")
+        });
 
-    run(state, terminal)?;
+    // setup terminal, which is restored when the guard is dropped, or on a panic of
+    // the main thread
+    terminal::install_panic_hook();
+    let _guard = TerminalGuard::new()?;
+    let backend = CrosstermBackend::new(std::io::stdout());
+    let terminal = Terminal::new(backend)?;
 
-    disable_raw_mode()?;
-    execute!(std::io::stdout(), LeaveAlternateScreen)?;
+    let events = Events::new(&folder_path);
+    let mut state = State::new(
+        folder_path,
+        editor,
+        args.sorting,
+        args.reverse,
+        config,
+        events.spawner(),
+    );
+    state.update_files()?;
+    if let Some(path) = select {
+        finder::select_path(&mut state, path)?;
+    }
 
-    Ok(())
+    run(state, terminal, events).inspect_err(|e| simple_log::log::error!("exited: {}", e))
 }
 
-fn run(mut state: State, mut terminal: CrossTerminal) -> io::Result<()> {
+fn run(mut state: State, mut terminal: CrossTerminal, events: Events) -> io::Result<()> {
     let state = &mut state;
     let terminal = &mut terminal;
+    let mut mouse = Mouse::default();
     terminal.draw(|f| ui(f, state))?;
     loop {
-        if let Event::Key(key) = event::read()? {
-            match key.code {
-                KeyCode::Char('q') => return Ok(()),
-                KeyCode::Esc => {
-                    state.key_state_machine.reset();
-                }
-                _ => {
-                    let result = state.key_state_machine.register_event(key);
-                    if let Some(kb) = result {
-                        let count = state.key_state_machine.count();
-                        (kb.action)(state, terminal, count)?;
+        let mut redraw = false;
+        let mut files_changed = false;
+        for event in events.next_batch() {
+            match event {
+                AppEvent::Input(input) => match input? {
+                    Event::Key(key) => {
+                        match handle_key(state, terminal, key) {
+                            Ok(true) => return Ok(()),
+                            Ok(false) => {}
+                            Err(e) => state.messages.error(e.to_string()),
+                        }
+                        redraw = true;
+                    }
+                    Event::Mouse(event) => {
+                        if let Err(e) = mouse.handle(state, terminal, event) {
+                            state.messages.error(e.to_string());
+                        }
+                        redraw = true;
                     }
+                    Event::Resize(_, _) => redraw = true,
+                    _ => {}
+                },
+                AppEvent::Tick => redraw |= state.tick(),
+                // one refresh is enough for everything that happened at once
+                AppEvent::FilesChanged => files_changed = true,
+                AppEvent::Completed(completion) => {
+                    if let Err(e) = completion(state) {
+                        state.messages.error(e.to_string());
+                    }
+                    redraw = true;
                 }
             }
+        }
+        if files_changed {
+            if let Err(e) = state.refresh() {
+                state.messages.error(format!("refresh: {}", e));
+            }
+            redraw = true;
+        }
+        if redraw {
             terminal.draw(|f| ui(f, state))?;
         }
     }
 }
 
-fn ui<B: Backend>(f: &mut Frame<B>, state: &State) {
+/// Handles a key press in the current mode. Returns whether to quit. Errors are
+/// shown in the footer by the caller, the app keeps running.
+fn handle_key(state: &mut State, terminal: &mut CrossTerminal, key: KeyEvent) -> io::Result<bool> {
+    state.messages.dismiss();
+    match state.mode {
+        Mode::Search(_) => search::handle_key(state, key)?,
+        Mode::ContentSearch(_) => content_search::handle_prompt_key(state, key)?,
+        Mode::Finder(_) => finder::handle_key(state, key)?,
+        Mode::Rename(_) => rename::handle_key(state, key)?,
+        Mode::Confirm(_) => confirm::handle_key(state, terminal, key)?,
+        Mode::Trash(_) => trash::handle_key(state, terminal, key)?,
+        Mode::Help(_) => help::handle_key(state, key)?,
+        Mode::Messages(_) => messages::handle_key(state, key)?,
+        Mode::Command(_) => return command::handle_key(state, terminal, key),
+        Mode::Palette(_) => palette::handle_key(state, terminal, key)?,
+        Mode::ContentResults(_) => content_search::handle_results_key(state, terminal, key)?,
+        Mode::Normal => match key.code {
+            KeyCode::Char('q') => return Ok(true),
+            KeyCode::Esc => {
+                state.key_state_machine.reset();
+                state.search_query = None;
+                state.visual_anchor = None;
+            }
+            _ => {
+                let visual = state.visual_anchor.is_some();
+                let result = state.key_state_machine.register_event(key, visual);
+                if let Some(kb) = result {
+                    let count = state.key_state_machine.count();
+                    let action = kb.action;
+                    (action.function)(state, terminal, count)
+                        .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", action.name, e)))?;
+                }
+            }
+        },
+    }
+    Ok(false)
+}
+
+/// Splits `area` into the header, the main area and the footer, and the main area
+/// into the file list, a gap and the file view.
+pub fn layout(area: Rect) -> (Vec<Rect>, Vec<Rect>) {
     let v_chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints(vec![
@@ -93,22 +218,7 @@ fn ui<B: Backend>(f: &mut Frame<B>, state: &State) {
             Constraint::Min(5),
             Constraint::Length(2),
         ])
-        .split(f.size());
-    let header = Paragraph::new(state.cwd.as_os_str().to_string_lossy())
-        .block(Block::default().borders(Borders::BOTTOM));
-    f.render_widget(header, v_chunks[0]);
-
-    let footer = Paragraph::new(
-        state
-            .key_state_machine
-            .current_keys
-            .iter()
-            .map(KeyBindingPart::to_string)
-            .collect::<Vec<String>>()
-            .join(""),
-    ).block(Block::default().borders(Borders::TOP));
-    f.render_widget(footer, v_chunks[2]);
-
+        .split(area);
     let h_chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints(vec![
@@ -117,20 +227,517 @@ fn ui<B: Backend>(f: &mut Frame<B>, state: &State) {
             Constraint::Percentage(60),
         ])
         .split(v_chunks[1]);
+    (v_chunks, h_chunks)
+}
+
+fn ui<B: Backend>(f: &mut Frame<B>, state: &State) {
+    let (v_chunks, h_chunks) = layout(f.size());
+    // dialogs are drawn over the view they were opened from
+    let view_mode = match &state.mode {
+        Mode::Confirm(dialog) => &dialog.previous,
+        mode => mode,
+    };
+    let header_text = match view_mode {
+        Mode::ContentResults(results) if results.searching => format!(
+            "searching for \"{}\" in {}…",
+            results.query,
+            state.root.display()
+        ),
+        Mode::ContentResults(results) => format!(
+            "{} matches for \"{}\" in {}",
+            results.matches.len(),
+            results.query,
+            state.root.display()
+        ),
+        Mode::Trash(view) => format!(
+            "trash ({} files)   {}",
+            view.entries.len(),
+            trash::key_hints(state)
+        ),
+        _ => state.relative_cwd().to_string_lossy().into_owned(),
+    };
+    let header = Paragraph::new(header_text).block(Block::default().borders(Borders::BOTTOM));
+    f.render_widget(header, v_chunks[0]);
+
+    let footer_area = v_chunks[2];
+    let footer_text: Spans = match &state.mode {
+        Mode::Search(search) => prompt(f, footer_area, "/", &search.input).into(),
+        Mode::ContentSearch(input) => prompt(f, footer_area, "content: ", input).into(),
+        Mode::Command(command) => {
+            let mut spans = vec![Span::raw(prompt(f, footer_area, ":", &command.input))];
+            if let Some((index, count)) = command.completion_position() {
+                spans.push(Span::styled(
+                    format!("  ({}/{})", index, count),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            Spans::from(spans)
+        }
+        Mode::Rename(rename) => match &rename.error {
+            Some(error) => format!("rename: {}", error).into(),
+            None => "rename: Enter to confirm, Esc to cancel".into(),
+        },
+        _ => {
+            let keys = state
+                .key_state_machine
+                .current_keys
+                .iter()
+                .map(KeyBindingPart::to_string)
+                .collect::<Vec<String>>()
+                .join("");
+            match (state.visual_anchor, state.messages.current()) {
+                (_, Some(message)) => {
+                    Span::styled(message.text.clone(), severity_style(message.severity)).into()
+                }
+                (Some(_), None) => format!("-- VISUAL -- {}", keys).into(),
+                (None, None) => keys.into(),
+            }
+        }
+    };
+    let footer = Paragraph::new(footer_text).block(Block::default().borders(Borders::TOP));
+    f.render_widget(footer, v_chunks[2]);
+
     let file_list_block = Block::default();
-    let list_items: Vec<ListItem> = state.file_names().into_iter().map(ListItem::new).collect();
+    let (list_items, mut list_state): (Vec<ListItem>, _) = match view_mode {
+        Mode::ContentResults(results) => (
+            results
+                .matches
+                .iter()
+                .map(|m| {
+                    let path = m.path.strip_prefix(&state.root).unwrap_or(&m.path);
+                    let mut spans = vec![Span::styled(
+                        format!("{}:{}: ", path.display(), m.line),
+                        Style::default().fg(Color::Blue),
+                    )];
+                    spans.extend(highlight_match(&m.snippet, Some(&results.query)).0);
+                    ListItem::new(Spans::from(spans))
+                })
+                .collect(),
+            results.list_state.clone(),
+        ),
+        Mode::Trash(view) => (
+            view.entries
+                .iter()
+                .map(|entry| {
+                    let path = entry
+                        .original_path
+                        .strip_prefix(&state.root)
+                        .unwrap_or(&entry.original_path);
+                    ListItem::new(Spans::from(vec![
+                        Span::styled(
+                            format!("{} ", entry.deletion_date.replace('T', " ")),
+                            Style::default().fg(Color::Blue),
+                        ),
+                        Span::raw(path.display().to_string()),
+                    ]))
+                })
+                .collect(),
+            view.list_state.clone(),
+        ),
+        _ => (
+            state
+                .file_names()
+                .into_iter()
+                .enumerate()
+                .map(|(i, name)| {
+                    let item = ListItem::new(highlight_match(&name, state.search_query.as_deref()));
+                    match state.visual_range() {
+                        Some(range) if range.contains(&i) => item.style(visual_style()),
+                        _ => item,
+                    }
+                })
+                .collect(),
+            state.list_state.clone(),
+        ),
+    };
     let list = List::new(list_items)
         .block(file_list_block)
-        .highlight_style(
-            Style::default()
-                .bg(Color::Gray)
-                .fg(Color::Black)
-                .add_modifier(Modifier::BOLD),
-        );
-    let mut list_state = state.list_state.clone();
+        .highlight_style(selection_style());
     f.render_stateful_widget(list, h_chunks[0], &mut list_state);
 
+    if let Mode::Rename(rename) = &state.mode {
+        // the list always scrolls just enough to show the selection
+        if let Some(selected) = list_state.selected() {
+            let area = h_chunks[0];
+            let row = selected.min(area.height.saturating_sub(1) as usize) as u16;
+            let row_area = Rect::new(area.x, area.y + row, area.width, 1);
+            let editor = Paragraph::new(rename.input.text())
+                .style(Style::default().bg(Color::Blue).fg(Color::White));
+            f.render_widget(Clear, row_area);
+            f.render_widget(editor, row_area);
+            f.set_cursor(row_area.x + rename.input.cursor_width() as u16, row_area.y);
+        }
+    }
+
     let file_view_block = Block::default();
-    let file_view_text = Paragraph::new(state.file_view_content.as_str()).block(file_view_block);
-    f.render_widget(file_view_text, h_chunks[2]);
+    let file_view_area = h_chunks[2];
+    let content = state.file_view_content.as_str();
+    let mut file_view_text = match &state.file_view_path {
+        Some(path) if markdown::is_markdown(path) => markdown::highlight(content),
+        _ => Text::raw(content),
+    };
+    if state.wrap {
+        file_view_text = wrap::wrap(file_view_text, content, file_view_area.width as usize);
+    }
+    let line_count = state.file_view_line_count(file_view_area.width as usize);
+    // the file may have become shorter since it was scrolled
+    let scroll = state.file_view_scroll.min(line_count.saturating_sub(1));
+    let file_view_text = Paragraph::new(file_view_text)
+        .block(file_view_block)
+        .scroll((scroll as u16, 0));
+    f.render_widget(file_view_text, file_view_area);
+    let position = scroll_position(scroll, file_view_area.height as usize, line_count);
+    let position_area = Rect::new(footer_area.x, footer_area.y + 1, footer_area.width, 1);
+    f.render_widget(
+        Paragraph::new(position).alignment(Alignment::Right),
+        position_area,
+    );
+
+    match &state.mode {
+        Mode::Finder(finder) => finder_ui(f, finder),
+        Mode::Palette(palette) => palette_ui(f, palette),
+        Mode::Confirm(dialog) => confirm_ui(f, dialog),
+        Mode::Help(help) => help_ui(f, state, help),
+        Mode::Messages(history) => messages_ui(f, state, history),
+        Mode::Normal => which_key_ui(f, state, v_chunks[1]),
+        _ => {}
+    }
+}
+
+fn confirm_ui<B: Backend>(f: &mut Frame<B>, dialog: &ConfirmDialog) {
+    let size = f.size();
+    // leave room for the borders, the hint and some of the screen around the dialog
+    let max_items = (size.height.saturating_sub(8) as usize).max(1);
+    let mut lines: Vec<Spans> = dialog
+        .items
+        .iter()
+        .take(max_items)
+        .map(|item| Spans::from(format!(" {}", item)))
+        .collect();
+    if dialog.items.len() > max_items {
+        lines.pop();
+        lines.push(Spans::from(format!(
+            " ... and {} more",
+            dialog.items.len() - max_items + 1
+        )));
+    }
+    lines.push(Spans::default());
+    lines.push(Spans::from(vec![
+        Span::styled(" y", Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(": yes  "),
+        Span::styled("n", Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(": no"),
+    ]));
+
+    let width = lines
+        .iter()
+        .map(Spans::width)
+        .chain([dialog.title.width() + 2])
+        .max()
+        .unwrap_or_default() as u16
+        + 3;
+    let height = lines.len() as u16 + 2;
+    let area = Rect::new(
+        size.x + size.width.saturating_sub(width) / 2,
+        size.y + size.height.saturating_sub(height) / 2,
+        width.min(size.width),
+        height.min(size.height),
+    );
+    f.render_widget(Clear, area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" {} ", dialog.title));
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn finder_ui<B: Backend>(f: &mut Frame<B>, finder: &Finder) {
+    fuzzy_list_ui(f, "find", finder, |candidate, positions| {
+        ListItem::new(highlight_positions(candidate, positions))
+    });
+}
+
+fn palette_ui<B: Backend>(f: &mut Frame<B>, palette: &Palette) {
+    let keys_width = palette
+        .matches()
+        .map(|(candidate, _)| candidate.keys.width())
+        .max()
+        .unwrap_or(0);
+    fuzzy_list_ui(f, "actions", palette, |candidate, positions| {
+        let padding = " ".repeat(keys_width - candidate.keys.width() + 2);
+        let mut spans = vec![
+            Span::styled(candidate.keys.clone(), Style::default().fg(Color::Blue)),
+            Span::raw(padding),
+        ];
+        spans.extend(highlight_positions(&candidate.text, positions).0);
+        ListItem::new(Spans::from(spans))
+    });
+}
+
+/// The query of `list` with its matches below, each shown as `item` makes it from
+/// the candidate and the char indices of its matched chars.
+fn fuzzy_list_ui<B: Backend, T: FuzzyCandidate>(
+    f: &mut Frame<B>,
+    title: &str,
+    list: &FuzzyList<T>,
+    item: impl Fn(&T, &[usize]) -> ListItem<'static>,
+) {
+    let area = centered_rect(80, 80, f.size());
+    f.render_widget(Clear, area);
+    let block = Block::default().borders(Borders::ALL).title(format!(
+        " {} ({}/{}) ",
+        title,
+        list.match_count(),
+        list.candidate_count()
+    ));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Length(1), Constraint::Min(1)])
+        .split(inner);
+
+    f.render_widget(
+        Paragraph::new(format!("> {}", list.input.text())),
+        chunks[0],
+    );
+    f.set_cursor(
+        chunks[0].x + 2 + list.input.cursor_width() as u16,
+        chunks[0].y,
+    );
+
+    let items: Vec<ListItem> = list
+        .matches()
+        .map(|(candidate, positions)| item(candidate, positions))
+        .collect();
+    let list_widget = List::new(items).highlight_style(selection_style());
+    let mut list_state = list.list_state.clone();
+    f.render_stateful_widget(list_widget, chunks[1], &mut list_state);
+}
+
+fn help_ui<B: Backend>(f: &mut Frame<B>, state: &State, help: &HelpView) {
+    let area = centered_rect(80, 80, f.size());
+    f.render_widget(Clear, area);
+    let bindings = help::bindings(state);
+    let keys: Vec<String> = bindings
+        .iter()
+        .map(|binding| {
+            binding
+                .keys()
+                .iter()
+                .map(KeyBindingPart::to_string)
+                .collect()
+        })
+        .collect();
+    let keys_width = keys.iter().map(|keys| keys.width()).max().unwrap_or(0);
+    let items: Vec<ListItem> = bindings
+        .iter()
+        .zip(keys)
+        .map(|(binding, keys)| {
+            let action = binding.action;
+            let padding = " ".repeat(keys_width - keys.width() + 2);
+            let mut spans = vec![
+                Span::styled(keys, Style::default().fg(Color::Blue)),
+                Span::raw(padding),
+                Span::styled(
+                    format!("{:<11}", action.category.to_string()),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(
+                    if action.takes_count {
+                        "[count] "
+                    } else {
+                        "        "
+                    },
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::raw(action.description),
+            ];
+            if binding.visual_only {
+                spans.push(Span::styled(
+                    " (visual)",
+                    Style::default().fg(Color::Magenta),
+                ));
+            }
+            ListItem::new(Spans::from(spans))
+        })
+        .collect();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" key bindings (Esc to close) ");
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(selection_style());
+    let mut list_state = help.list_state.clone();
+    f.render_stateful_widget(list, area, &mut list_state);
+}
+
+fn messages_ui<B: Backend>(f: &mut Frame<B>, state: &State, history: &MessageHistory) {
+    let area = centered_rect(80, 80, f.size());
+    f.render_widget(Clear, area);
+    let items: Vec<ListItem> = state
+        .messages
+        .history()
+        .map(|message| {
+            ListItem::new(Spans::from(vec![
+                Span::styled(
+                    format!("{} ", message.time.format("%H:%M:%S")),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(
+                    format!("{:<8}", message.severity.to_string()),
+                    severity_style(message.severity),
+                ),
+                Span::raw(message.text.as_str()),
+            ]))
+        })
+        .collect();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" messages (Esc to close) ");
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(selection_style());
+    let mut list_state = history.list_state.clone();
+    f.render_stateful_widget(list, area, &mut list_state);
+}
+
+/// Lists the keys that can follow a started key sequence in the bottom right of `area`.
+fn which_key_ui<B: Backend>(f: &mut Frame<B>, state: &State, area: Rect) {
+    let pending = state.key_state_machine.pending_bindings();
+    if pending.is_empty() {
+        return;
+    }
+    let keys: Vec<String> = pending
+        .iter()
+        .map(|(keys, _)| {
+            keys.iter()
+                .map(KeyBindingPart::to_string)
+                .collect::<String>()
+        })
+        .collect();
+    let keys_width = keys.iter().map(|keys| keys.width()).max().unwrap_or(0);
+    let lines: Vec<Spans> = pending
+        .iter()
+        .zip(keys)
+        .map(|((_, binding), keys)| {
+            let padding = " ".repeat(keys_width - keys.width() + 2);
+            Spans::from(vec![
+                Span::styled(keys, Style::default().fg(Color::Blue)),
+                Span::raw(padding),
+                Span::raw(binding.action.name),
+            ])
+        })
+        .collect();
+    let width = lines.iter().map(Spans::width).max().unwrap_or(0) as u16 + 2;
+    let height = lines.len() as u16 + 2;
+    let popup = Rect::new(
+        area.right().saturating_sub(width),
+        area.bottom().saturating_sub(height),
+        width.min(area.width),
+        height.min(area.height),
+    );
+    f.render_widget(Clear, popup);
+    let paragraph = Paragraph::new(lines).block(Block::default().borders(Borders::ALL));
+    f.render_widget(paragraph, popup);
+}
+
+/// Places the cursor in the footer and returns the text of the prompt.
+fn prompt<B: Backend>(f: &mut Frame<B>, area: Rect, prefix: &str, input: &TextInput) -> String {
+    f.set_cursor(
+        area.x + (prefix.width() + input.cursor_width()) as u16,
+        area.y + 1,
+    );
+    format!("{}{}", prefix, input.text())
+}
+
+/// Where the file view is scrolled to, like vim's ruler: the first shown line out
+/// of all lines, and how far down that is.
+fn scroll_position(first: usize, height: usize, lines: usize) -> String {
+    if lines == 0 {
+        return String::new();
+    }
+    let location = if lines <= height && first == 0 {
+        "All".to_string()
+    } else if first == 0 {
+        "Top".to_string()
+    } else if first + height >= lines {
+        "Bot".to_string()
+    } else {
+        format!("{}%", first * 100 / (lines - height))
+    };
+    format!("{}/{} {}", first + 1, lines, location)
+}
+
+/// A rect of the given percentage of `area`'s size in its center.
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let width = area.width * percent_x / 100;
+    let height = area.height * percent_y / 100;
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+fn selection_style() -> Style {
+    Style::default()
+        .bg(Color::Gray)
+        .fg(Color::Black)
+        .add_modifier(Modifier::BOLD)
+}
+
+fn visual_style() -> Style {
+    Style::default().bg(Color::DarkGray)
+}
+
+fn severity_style(severity: Severity) -> Style {
+    match severity {
+        Severity::Info => Style::default(),
+        Severity::Warning => Style::default().fg(Color::Yellow),
+        Severity::Error => Style::default().fg(Color::Red),
+    }
+}
+
+fn match_style() -> Style {
+    Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+}
+
+/// Emphasizes the chars at the given char indices of `text`.
+fn highlight_positions(text: &str, positions: &[usize]) -> Spans<'static> {
+    let mut spans = Vec::new();
+    let mut current = String::new();
+    let mut current_matched = false;
+    for (i, c) in text.chars().enumerate() {
+        let matched = positions.contains(&i);
+        if matched != current_matched && !current.is_empty() {
+            spans.push(span(std::mem::take(&mut current), current_matched));
+        }
+        current_matched = matched;
+        current.push(c);
+    }
+    spans.push(span(current, current_matched));
+    Spans::from(spans)
+}
+
+fn span(text: String, matched: bool) -> Span<'static> {
+    if matched {
+        Span::styled(text, match_style())
+    } else {
+        Span::raw(text)
+    }
+}
+
+/// Emphasizes the first match of `query` in `text`.
+fn highlight_match(text: &str, query: Option<&str>) -> Spans<'static> {
+    match query.and_then(|query| search::find_match(text, query)) {
+        Some(range) => Spans::from(vec![
+            Span::raw(text[..range.start].to_string()),
+            Span::styled(text[range.clone()].to_string(), match_style()),
+            Span::raw(text[range.end..].to_string()),
+        ]),
+        None => Spans::from(text.to_string()),
+    }
 }
@@ -0,0 +1,229 @@
+//! Highlighting of markdown for the file view. This is a line based
+//! approximation, not a full CommonMark parser: it only has to look right for
+//! the kind of notes this app is meant for.
+
+use std::path::Path;
+
+use tui::{
+    style::{Color, Modifier, Style},
+    text::{Span, Spans, Text},
+};
+
+pub fn is_markdown(path: &Path) -> bool {
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    ["md", "markdown", "mdown", "mkd"]
+        .iter()
+        .any(|e| extension.eq_ignore_ascii_case(e))
+}
+
+pub fn highlight(text: &str) -> Text<'_> {
+    let mut fence: Option<&str> = None;
+    let lines = text
+        .lines()
+        .map(|line| {
+            let trimmed = line.trim_start();
+            let is_fence_line = trimmed.starts_with("```") || trimmed.starts_with("~~~");
+            match fence {
+                Some(marker) => {
+                    if is_fence_line && trimmed.starts_with(marker) {
+                        fence = None;
+                    }
+                    Spans::from(Span::styled(line, code_style()))
+                }
+                None if is_fence_line => {
+                    fence = Some(&trimmed[..3]);
+                    Spans::from(Span::styled(line, code_style()))
+                }
+                None => highlight_line(line),
+            }
+        })
+        .collect::<Vec<_>>();
+    Text::from(lines)
+}
+
+fn highlight_line(line: &str) -> Spans<'_> {
+    let indent_len = line.len() - line.trim_start().len();
+    let (indent, rest) = line.split_at(indent_len);
+
+    if let Some(level) = heading_level(rest) {
+        let mut style = Style::default()
+            .fg(Color::Magenta)
+            .add_modifier(Modifier::BOLD);
+        if level == 1 {
+            style = style.add_modifier(Modifier::UNDERLINED);
+        }
+        return Spans::from(Span::styled(line, style));
+    }
+
+    let mut spans = vec![Span::raw(indent)];
+    if let Some(quoted) = rest.strip_prefix('>') {
+        spans.push(Span::styled(">", Style::default().fg(Color::DarkGray)));
+        let quote_style = Style::default()
+            .fg(Color::Gray)
+            .add_modifier(Modifier::ITALIC);
+        spans.extend(inline(quoted, quote_style));
+        return Spans::from(spans);
+    }
+
+    let mut rest = rest;
+    if let Some(marker_len) = list_marker_len(rest) {
+        let (marker, after) = rest.split_at(marker_len);
+        spans.push(Span::styled(marker, Style::default().fg(Color::Yellow)));
+        rest = after;
+        if let Some(checkbox) = checkbox(rest) {
+            let (checkbox_text, after) = rest.split_at(3);
+            let color = if checkbox { Color::Green } else { Color::Red };
+            spans.push(Span::styled(
+                checkbox_text,
+                Style::default().fg(color).add_modifier(Modifier::BOLD),
+            ));
+            rest = after;
+        }
+    }
+    spans.extend(inline(rest, Style::default()));
+    Spans::from(spans)
+}
+
+/// The level of an ATX heading like `## Title`.
+fn heading_level(line: &str) -> Option<usize> {
+    let level = line.bytes().take_while(|b| *b == b'#').count();
+    let after = &line[level..];
+    if (1..=6).contains(&level) && (after.is_empty() || after.starts_with(' ')) {
+        Some(level)
+    } else {
+        None
+    }
+}
+
+/// The length of a list marker like `- `, `* `, `+ ` or `12. ` including the space.
+fn list_marker_len(line: &str) -> Option<usize> {
+    let bytes = line.as_bytes();
+    let digits = bytes.iter().take_while(|b| b.is_ascii_digit()).count();
+    let marker_len = match bytes.first()? {
+        b'-' | b'*' | b'+' => 1,
+        _ if (1..=9).contains(&digits) && matches!(bytes.get(digits), Some(b'.' | b')')) => {
+            digits + 1
+        }
+        _ => return None,
+    };
+    match bytes.get(marker_len) {
+        Some(b' ') => Some(marker_len + 1),
+        _ => None,
+    }
+}
+
+/// Whether the text after a list marker starts with a checked or unchecked task box.
+fn checkbox(text: &str) -> Option<bool> {
+    match text.get(..3)? {
+        "[ ]" => Some(false),
+        "[x]" | "[X]" => Some(true),
+        _ => None,
+    }
+}
+
+/// Highlights code spans, emphasis and links in `text`.
+fn inline(text: &str, base: Style) -> Vec<Span<'_>> {
+    let mut spans = Vec::new();
+    let mut plain_start = 0;
+    let mut i = 0;
+    let bytes = text.as_bytes();
+    while i < bytes.len() {
+        let found = match bytes[i] {
+            b'`' => code_span(text, i),
+            b'*' | b'_' | b'~' => emphasis(text, i, base),
+            b'[' => link(text, i, base),
+            _ => None,
+        };
+        match found {
+            Some((end, styled)) => {
+                if plain_start < i {
+                    spans.push(Span::styled(&text[plain_start..i], base));
+                }
+                spans.extend(styled);
+                i = end;
+                plain_start = end;
+            }
+            None => i += 1,
+        }
+    }
+    if plain_start < text.len() {
+        spans.push(Span::styled(&text[plain_start..], base));
+    }
+    spans
+}
+
+/// A code span starting at `start`, closed by a backtick run of the same length.
+fn code_span(text: &str, start: usize) -> Option<(usize, Vec<Span<'_>>)> {
+    let ticks = text[start..].bytes().take_while(|b| *b == b'`').count();
+    let delimiter = &text[start..start + ticks];
+    let content_start = start + ticks;
+    let close = content_start + text[content_start..].find(delimiter)?;
+    let end = close + ticks;
+    Some((end, vec![Span::styled(&text[start..end], code_style())]))
+}
+
+/// `**strong**`, `__strong__`, `*emphasis*`, `_emphasis_` or `~~strikethrough~~`.
+fn emphasis(text: &str, start: usize, base: Style) -> Option<(usize, Vec<Span<'_>>)> {
+    let c = text.as_bytes()[start];
+    let run = text[start..].bytes().take_while(|b| *b == c).count();
+    let (delimiter_len, modifier) = match (c, run) {
+        (b'~', 2) => (2, Modifier::CROSSED_OUT),
+        (b'~', _) => return None,
+        (_, 1) => (1, Modifier::ITALIC),
+        (_, 2) => (2, Modifier::BOLD),
+        _ => return None,
+    };
+    // underscores inside words, as in snake_case, don't start emphasis
+    if c == b'_' && start > 0 && text.as_bytes()[start - 1].is_ascii_alphanumeric() {
+        return None;
+    }
+    let delimiter = &text[start..start + delimiter_len];
+    let content_start = start + delimiter_len;
+    if text[content_start..].starts_with(' ') {
+        return None;
+    }
+    let close = content_start + find_closing(&text[content_start..], delimiter, c)?;
+    let end = close + delimiter_len;
+    let style = base.add_modifier(modifier);
+    let mut spans = vec![Span::styled(delimiter, style)];
+    spans.extend(inline(&text[content_start..close], style));
+    spans.push(Span::styled(delimiter, style));
+    Some((end, spans))
+}
+
+/// The position of the `delimiter` closing an emphasis, which has to be exactly
+/// as long as the opening one and can't follow a space.
+fn find_closing(text: &str, delimiter: &str, c: u8) -> Option<usize> {
+    let bytes = text.as_bytes();
+    let mut from = 0;
+    while let Some(offset) = text[from..].find(delimiter) {
+        let position = from + offset;
+        let after = position + delimiter.len();
+        let longer = bytes.get(after) == Some(&c) || (position > 0 && bytes[position - 1] == c);
+        if position > 0 && bytes[position - 1] != b' ' && !longer {
+            return Some(position);
+        }
+        from = after;
+    }
+    None
+}
+
+/// `[text](target)`
+fn link(text: &str, start: usize, base: Style) -> Option<(usize, Vec<Span<'_>>)> {
+    let label_end = start + text[start..].find("](")?;
+    let target_end = label_end + 2 + text[label_end + 2..].find(')')?;
+    let label_style = base.fg(Color::Blue).add_modifier(Modifier::UNDERLINED);
+    let target_style = Style::default().fg(Color::DarkGray);
+    Some((
+        target_end + 1,
+        vec![
+            Span::styled("[", target_style),
+            Span::styled(&text[start + 1..label_end], label_style),
+            Span::styled(&text[label_end..=target_end], target_style),
+        ],
+    ))
+}
+
+fn code_style() -> Style {
+    Style::default().fg(Color::Cyan)
+}
@@ -0,0 +1,156 @@
+//! Messages for the footer, like the errors of failed actions, and the history
+//! of recent ones.
+
+use std::{
+    collections::VecDeque,
+    fmt::Display,
+    io::Result,
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Local};
+use crossterm::event::{KeyCode, KeyEvent};
+use simple_log::log;
+use tui::widgets::ListState;
+
+use crate::state::{Mode, State};
+
+/// How long info and warnings stay in the footer. Errors stay until the next key.
+const MESSAGE_DURATION: Duration = Duration::from_secs(5);
+/// How many messages the history keeps.
+const HISTORY_LENGTH: usize = 100;
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+pub struct Message {
+    pub severity: Severity,
+    pub text: String,
+    pub time: DateTime<Local>,
+}
+
+#[derive(Default)]
+pub struct Messages {
+    history: VecDeque<Message>,
+    /// when the last message was shown, if it still is
+    shown_since: Option<Instant>,
+}
+
+impl Messages {
+    pub fn info<S: Into<String>>(&mut self, text: S) {
+        self.push(Severity::Info, text.into());
+    }
+
+    pub fn warning<S: Into<String>>(&mut self, text: S) {
+        self.push(Severity::Warning, text.into());
+    }
+
+    pub fn error<S: Into<String>>(&mut self, text: S) {
+        self.push(Severity::Error, text.into());
+    }
+
+    /// Adds a message to the history, shows it in the footer and logs it.
+    fn push(&mut self, severity: Severity, text: String) {
+        match severity {
+            Severity::Info => log::info!("{}", text),
+            Severity::Warning => log::warn!("{}", text),
+            Severity::Error => log::error!("{}", text),
+        }
+        if self.history.len() == HISTORY_LENGTH {
+            self.history.pop_front();
+        }
+        self.history.push_back(Message {
+            severity,
+            text,
+            time: Local::now(),
+        });
+        self.shown_since = Some(Instant::now());
+    }
+
+    /// The message to show in the footer, if any.
+    pub fn current(&self) -> Option<&Message> {
+        self.shown_since.and(self.history.back())
+    }
+
+    /// Stops showing the current message.
+    pub fn dismiss(&mut self) {
+        self.shown_since = None;
+    }
+
+    /// Hides info and warnings once they have been shown for long enough. Returns
+    /// whether that happened.
+    pub fn expire(&mut self) -> bool {
+        let expired = match (self.shown_since, self.history.back()) {
+            (Some(since), Some(message)) => {
+                message.severity < Severity::Error && since.elapsed() > MESSAGE_DURATION
+            }
+            _ => false,
+        };
+        if expired {
+            self.dismiss();
+        }
+        expired
+    }
+
+    /// All kept messages, oldest first.
+    pub fn history(&self) -> impl Iterator<Item = &Message> {
+        self.history.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.history.len()
+    }
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The popup listing recent messages.
+pub struct MessageHistory {
+    pub list_state: ListState,
+}
+
+impl MessageHistory {
+    /// Starts with the newest message selected.
+    pub fn new(messages: &Messages) -> Self {
+        let mut list_state = ListState::default();
+        list_state.select(messages.len().checked_sub(1));
+        MessageHistory { list_state }
+    }
+}
+
+pub fn handle_key(state: &mut State, key: KeyEvent) -> Result<()> {
+    let last = state.messages.len().saturating_sub(1);
+    let Mode::Messages(history) = &mut state.mode else {
+        return Ok(());
+    };
+    let Some(selected) = history.list_state.selected() else {
+        state.mode = Mode::Normal;
+        return Ok(());
+    };
+    let new_selection = match key.code {
+        KeyCode::Char('j') | KeyCode::Down => (selected + 1).min(last),
+        KeyCode::Char('k') | KeyCode::Up => selected.saturating_sub(1),
+        KeyCode::Char('g') | KeyCode::Home => 0,
+        KeyCode::Char('G') | KeyCode::End => last,
+        KeyCode::Esc | KeyCode::Char('q') => {
+            state.mode = Mode::Normal;
+            return Ok(());
+        }
+        _ => return Ok(()),
+    };
+    history.list_state.select(Some(new_selection));
+    Ok(())
+}
@@ -0,0 +1,136 @@
+use std::{
+    io::Result,
+    time::{Duration, Instant},
+};
+
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+use tui::layout::Rect;
+
+use crate::{
+    content_search,
+    state::{updates, Mode, State},
+    trash, CrossTerminal,
+};
+
+/// Two clicks on the same row within this time are a double click.
+const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(400);
+/// How many lines one step of the scroll wheel moves.
+const SCROLL_LINES: usize = 3;
+
+/// Remembers the last click to recognize double clicks.
+#[derive(Default)]
+pub struct Mouse {
+    last_click: Option<(Instant, usize)>,
+}
+
+impl Mouse {
+    pub fn handle(
+        &mut self,
+        state: &mut State,
+        terminal: &mut CrossTerminal,
+        event: MouseEvent,
+    ) -> Result<()> {
+        let (_, h_chunks) = crate::layout(terminal.size()?);
+        let (list_area, file_view_area) = (h_chunks[0], h_chunks[2]);
+        let over_list = contains(list_area, event.column, event.row);
+        let over_file_view = contains(file_view_area, event.column, event.row);
+        match event.kind {
+            MouseEventKind::Down(MouseButton::Left) if over_list => {
+                let row = (event.row - list_area.y) as usize;
+                let Some(index) = list_index(state, row, list_area.height as usize) else {
+                    return Ok(());
+                };
+                let now = Instant::now();
+                let double_click = self.last_click.is_some_and(|(time, last_index)| {
+                    last_index == index && now - time < DOUBLE_CLICK_TIME
+                });
+                self.last_click = if double_click {
+                    None
+                } else {
+                    Some((now, index))
+                };
+                select(state, index)?;
+                if double_click {
+                    open(state, terminal)?;
+                }
+            }
+            MouseEventKind::ScrollDown if over_list => scroll_list(state, terminal, true)?,
+            MouseEventKind::ScrollUp if over_list => scroll_list(state, terminal, false)?,
+            MouseEventKind::ScrollDown if over_file_view => {
+                state.scroll_file_view(SCROLL_LINES as isize, file_view_area.width as usize)
+            }
+            MouseEventKind::ScrollUp if over_file_view => {
+                state.scroll_file_view(-(SCROLL_LINES as isize), file_view_area.width as usize)
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+fn contains(area: Rect, column: u16, row: u16) -> bool {
+    (area.left()..area.right()).contains(&column) && (area.top()..area.bottom()).contains(&row)
+}
+
+/// The index of the entry shown in `row` of the list in the current view, if any.
+fn list_index(state: &State, row: usize, height: usize) -> Option<usize> {
+    let (selected, len) = match &state.mode {
+        Mode::Normal => (state.list_state.selected(), state.files().len()),
+        Mode::ContentResults(results) => (results.list_state.selected(), results.matches.len()),
+        Mode::Trash(view) => (view.list_state.selected(), view.entries.len()),
+        _ => return None,
+    };
+    // the list always scrolls just enough to show the selection
+    let offset = selected
+        .unwrap_or(0)
+        .saturating_sub(height.saturating_sub(1));
+    Some(offset + row).filter(|index| *index < len)
+}
+
+fn select(state: &mut State, index: usize) -> Result<()> {
+    match &mut state.mode {
+        Mode::Normal => state.select(Some(index)),
+        Mode::ContentResults(results) => {
+            results.list_state.select(Some(index));
+            content_search::update_preview(state);
+            Ok(())
+        }
+        Mode::Trash(view) => {
+            view.list_state.select(Some(index));
+            trash::update_preview(state);
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+fn open(state: &mut State, terminal: &mut CrossTerminal) -> Result<()> {
+    match &state.mode {
+        Mode::Normal => {
+            // open the clicked file, not the whole visual range
+            state.visual_anchor = None;
+            updates::open_selected(state, terminal, 0)
+        }
+        Mode::ContentResults(_) => content_search::open_selected_match(state, terminal),
+        _ => Ok(()),
+    }
+}
+
+fn scroll_list(state: &mut State, terminal: &mut CrossTerminal, down: bool) -> Result<()> {
+    let (selected, len) = match &state.mode {
+        Mode::Normal if down => return updates::selection_down(state, terminal, SCROLL_LINES),
+        Mode::Normal => return updates::selection_up(state, terminal, SCROLL_LINES),
+        Mode::ContentResults(results) => (results.list_state.selected(), results.matches.len()),
+        Mode::Trash(view) => (view.list_state.selected(), view.entries.len()),
+        _ => return Ok(()),
+    };
+    let Some(selected) = selected else {
+        return Ok(());
+    };
+    let index = if down {
+        (selected + SCROLL_LINES).min(len - 1)
+    } else {
+        selected.saturating_sub(SCROLL_LINES)
+    };
+    select(state, index)
+}
@@ -0,0 +1,96 @@
+use std::io::{Error, Result};
+
+use crossterm::event::KeyEvent;
+
+use crate::{
+    actions::{NamedAction, ACTIONS},
+    fuzzy_list::{FuzzyCandidate, FuzzyList},
+    input::InputResult,
+    keybindings::KeyBindingPart,
+    state::{Mode, State},
+    trash, CrossTerminal,
+};
+
+/// The command palette overlay, which finds actions by name or description and
+/// runs them.
+pub type Palette = FuzzyList<Candidate>;
+
+pub struct Candidate {
+    action: &'static NamedAction,
+    /// the name and description, aligned, which the query is matched against
+    pub text: String,
+    /// the key bindings of the action, if any
+    pub keys: String,
+}
+
+impl FuzzyCandidate for Candidate {
+    fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+/// Lists the actions by category, leaving out the ones that only do something in
+/// their own view, like restoring from the trash.
+pub fn new(state: &State) -> Palette {
+    let mut actions: Vec<&'static NamedAction> = ACTIONS
+        .iter()
+        .filter(|action| !matches!(action.name, "nop" | "palette"))
+        .filter(|action| {
+            !trash::VIEW_ACTIONS
+                .iter()
+                .any(|(name, _)| *name == action.name)
+        })
+        .collect();
+    actions.sort_by_key(|action| action.category);
+    let name_width = actions
+        .iter()
+        .map(|action| action.name.len())
+        .max()
+        .unwrap_or(0);
+    let candidates = actions
+        .into_iter()
+        .map(|action| Candidate {
+            action,
+            text: format!("{:<name_width$}  {}", action.name, action.description),
+            keys: keys(state, action),
+        })
+        .collect();
+    FuzzyList::new(candidates)
+}
+
+/// The keys bound to `action`, separated by commas.
+pub fn keys(state: &State, action: &NamedAction) -> String {
+    state
+        .key_state_machine
+        .key_bindings()
+        .iter()
+        .filter(|binding| binding.action.name == action.name)
+        .map(|binding| {
+            binding
+                .keys()
+                .iter()
+                .map(KeyBindingPart::to_string)
+                .collect::<String>()
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+pub fn handle_key(state: &mut State, terminal: &mut CrossTerminal, key: KeyEvent) -> Result<()> {
+    let Mode::Palette(palette) = &mut state.mode else {
+        return Ok(());
+    };
+    match palette.handle_key(key) {
+        InputResult::Changed | InputResult::Unchanged => {}
+        InputResult::Cancel => state.mode = Mode::Normal,
+        InputResult::Confirm => {
+            let action = palette.selected().map(|candidate| candidate.action);
+            state.mode = Mode::Normal;
+            if let Some(action) = action {
+                return (action.function)(state, terminal, 0)
+                    .map_err(|e| Error::new(e.kind(), format!("{}: {}", action.name, e)));
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,99 @@
+//! What the file view shows for a path: the start of a text file, a hex dump of a
+//! binary file or the entries of a folder.
+
+use std::{
+    fmt::Write,
+    fs::File,
+    io::{Read, Result},
+    path::Path,
+};
+
+use crate::util;
+
+/// How much of a file is read for the preview.
+const READ_LIMIT: usize = 64 * 1024;
+/// How much of a binary file is shown as hex.
+const HEX_LIMIT: usize = 256;
+const HEX_BYTES_PER_LINE: usize = 8;
+
+/// The text to show in the file view for `path`.
+pub fn load(path: &Path) -> Result<String> {
+    if path.is_dir() {
+        return folder(path);
+    }
+    let size = path.metadata()?.len();
+    let mut bytes = Vec::new();
+    File::open(path)?
+        .take(READ_LIMIT as u64)
+        .read_to_end(&mut bytes)?;
+    if util::is_binary(&bytes) {
+        return Ok(hex_summary(&bytes, size));
+    }
+    let truncated = (bytes.len() as u64) < size;
+    if truncated {
+        // the limit may have cut a multi-byte char in half
+        if let Err(e) = std::str::from_utf8(&bytes) {
+            if e.error_len().is_none() {
+                bytes.truncate(e.valid_up_to());
+            }
+        }
+    }
+    let mut text = String::from_utf8_lossy(&bytes).into_owned();
+    if truncated {
+        let _ = write!(
+            text,
+            "\n\n[only the first {} KiB of {} bytes are shown]",
+            READ_LIMIT / 1024,
+            size
+        );
+    }
+    Ok(text)
+}
+
+/// The entries of a folder, one per line, with a slash after subfolders.
+fn folder(path: &Path) -> Result<String> {
+    let mut entries: Vec<String> = std::fs::read_dir(path)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+                name + "/"
+            } else {
+                name
+            }
+        })
+        .collect();
+    if entries.is_empty() {
+        return Ok("empty folder".to_string());
+    }
+    entries.sort_by(|a, b| natord::compare(a, b));
+    Ok(entries.join("\n"))
+}
+
+/// A placeholder naming the size, followed by a hex dump of the first bytes.
+fn hex_summary(bytes: &[u8], size: u64) -> String {
+    let mut text = format!("binary file, {} bytes\n", size);
+    for (i, line) in bytes
+        .chunks(HEX_BYTES_PER_LINE)
+        .take(HEX_LIMIT / HEX_BYTES_PER_LINE)
+        .enumerate()
+    {
+        let _ = write!(text, "\n{:08x}  ", i * HEX_BYTES_PER_LINE);
+        for b in line {
+            let _ = write!(text, "{:02x} ", b);
+        }
+        let padding = (HEX_BYTES_PER_LINE - line.len()) * 3;
+        text.push_str(&" ".repeat(padding + 1));
+        text.extend(line.iter().map(|&b| {
+            if b.is_ascii_graphic() || b == b' ' {
+                b as char
+            } else {
+                '.'
+            }
+        }));
+    }
+    if bytes.len() > HEX_LIMIT {
+        text.push_str("\n…");
+    }
+    text
+}
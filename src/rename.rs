@@ -0,0 +1,62 @@
+use std::{
+    io::Result,
+    path::{Path, PathBuf},
+};
+
+use crossterm::event::KeyEvent;
+
+use crate::{
+    input::{InputResult, TextInput},
+    state::{Mode, State},
+};
+
+/// The inline editor over the selected row while renaming.
+pub struct RenameMode {
+    pub input: TextInput,
+    /// the file being renamed, which stays the same when the listing is reread
+    path: PathBuf,
+    /// why the last confirmed name was refused
+    pub error: Option<String>,
+}
+
+impl RenameMode {
+    /// Starts renaming the file at `index` with the cursor in front of the
+    /// extension, or at the very end if `after_extension` is set.
+    pub fn new(state: &State, index: usize, after_extension: bool) -> Self {
+        let file = &state.files()[index];
+        let name = file.name.to_string_lossy().into_owned();
+        let cursor = match Path::new(&name).extension() {
+            Some(extension) if !after_extension => name.len() - extension.len() - 1,
+            _ => name.len(),
+        };
+        RenameMode {
+            input: TextInput::with_cursor(name, cursor),
+            path: file.path.clone(),
+            error: None,
+        }
+    }
+}
+
+pub fn handle_key(state: &mut State, key: KeyEvent) -> Result<()> {
+    let Mode::Rename(rename) = &mut state.mode else {
+        return Ok(());
+    };
+    match rename.input.handle_key(key) {
+        InputResult::Changed => rename.error = None,
+        InputResult::Unchanged => {}
+        InputResult::Cancel => state.mode = Mode::Normal,
+        InputResult::Confirm => {
+            let new_name = rename.input.text().to_string();
+            let path = rename.path.clone();
+            match state.rename_file(path, &new_name) {
+                Ok(()) => state.mode = Mode::Normal,
+                Err(e) => {
+                    if let Mode::Rename(rename) = &mut state.mode {
+                        rename.error = Some(e.to_string());
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
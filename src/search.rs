@@ -0,0 +1,134 @@
+use std::{
+    io::Result,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use crossterm::event::KeyEvent;
+
+use crate::{
+    input::{InputResult, TextInput},
+    state::{Mode, State},
+};
+
+/// The state of the `/` prompt while the query is being typed.
+pub struct SearchMode {
+    pub input: TextInput,
+    /// the file selected before searching, which stays the same when the listing
+    /// is reread
+    previous_selection: Option<PathBuf>,
+    previous_query: Option<String>,
+}
+
+impl SearchMode {
+    pub fn new(previous_selection: Option<PathBuf>, previous_query: Option<String>) -> Self {
+        SearchMode {
+            input: TextInput::new(""),
+            previous_selection,
+            previous_query,
+        }
+    }
+}
+
+/// Finds `query` in `name`, ignoring case unless `query` contains a capital (like
+/// vim's smartcase). Returns the byte range of the first match in `name`.
+pub fn find_match(name: &str, query: &str) -> Option<Range<usize>> {
+    if query.is_empty() {
+        return None;
+    }
+    let ignore_case = !query.chars().any(char::is_uppercase);
+    let eq = |a: char, b: char| {
+        if ignore_case {
+            a.to_lowercase().eq(b.to_lowercase())
+        } else {
+            a == b
+        }
+    };
+    name.char_indices().find_map(|(start, _)| {
+        let mut name_chars = name[start..].char_indices();
+        for q in query.chars() {
+            let (_, c) = name_chars.next()?;
+            if !eq(c, q) {
+                return None;
+            }
+        }
+        let end = name_chars
+            .next()
+            .map(|(i, _)| start + i)
+            .unwrap_or(name.len());
+        Some(start..end)
+    })
+}
+
+/// The indices of all files in the current listing whose name matches `query`.
+pub fn matching_indices(state: &State, query: &str) -> Vec<usize> {
+    state
+        .files()
+        .iter()
+        .enumerate()
+        .filter(|(_, f)| find_match(&f.name.to_string_lossy(), query).is_some())
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// The match after (or before) `current`, wrapping around at the ends of the list.
+pub fn next_match(matches: &[usize], current: Option<usize>, forward: bool) -> Option<usize> {
+    let (first, last) = (*matches.first()?, *matches.last()?);
+    let next = match (current, forward) {
+        (None, true) => first,
+        (None, false) => last,
+        (Some(current), true) => matches
+            .iter()
+            .copied()
+            .find(|i| *i > current)
+            .unwrap_or(first),
+        (Some(current), false) => matches
+            .iter()
+            .copied()
+            .rfind(|i| *i < current)
+            .unwrap_or(last),
+    };
+    Some(next)
+}
+
+/// The index of the file selected before searching, if it is still there, or else
+/// the current selection.
+fn previous_index(state: &State, path: Option<&Path>) -> Option<usize> {
+    let path = path?;
+    state
+        .files()
+        .iter()
+        .position(|f| f.path == path)
+        .or(state.list_state.selected())
+}
+
+pub fn handle_key(state: &mut State, key: KeyEvent) -> Result<()> {
+    let Mode::Search(search) = &mut state.mode else {
+        return Ok(());
+    };
+    let result = search.input.handle_key(key);
+    let query = search.input.text().to_string();
+    let previous_selection = search.previous_selection.clone();
+    let previous_query = search.previous_query.clone();
+    let previous_selection = previous_index(state, previous_selection.as_deref());
+    match result {
+        InputResult::Changed => {
+            let first = matching_indices(state, &query).first().copied();
+            state.search_query = Some(query).filter(|q| !q.is_empty());
+            state.select(first.or(previous_selection))?;
+        }
+        InputResult::Confirm => {
+            state.mode = Mode::Normal;
+            if query.is_empty() {
+                state.search_query = previous_query;
+            }
+        }
+        InputResult::Cancel => {
+            state.mode = Mode::Normal;
+            state.search_query = previous_query;
+            state.select(previous_selection)?;
+        }
+        InputResult::Unchanged => {}
+    }
+    Ok(())
+}
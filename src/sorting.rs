@@ -10,6 +10,22 @@ pub enum Sorting {
     Natural,
 }
 
+impl Sorting {
+    /// The names of the sortings, as used on the command line.
+    pub const NAMES: &'static [&'static str] = &["name", "ctime", "mtime", "size", "natural"];
+
+    pub fn from_name(name: &str) -> Option<Sorting> {
+        match name {
+            "name" => Some(Sorting::Name),
+            "ctime" => Some(Sorting::Ctime),
+            "mtime" => Some(Sorting::Mtime),
+            "size" => Some(Sorting::Size),
+            "natural" => Some(Sorting::Natural),
+            _ => None,
+        }
+    }
+}
+
 pub fn name(file1: &FileInfo, file2: &FileInfo) -> Ordering {
     file1.name.cmp(&file2.name)
 }
@@ -30,7 +46,7 @@ pub fn natural(file1: &FileInfo, file2: &FileInfo) -> Ordering {
     natord::compare(&file1.name.to_string_lossy(), &file2.name.to_string_lossy())
 }
 
-pub fn sort_files(files: &mut Vec<FileInfo>, sorting: &Sorting) {
+pub fn sort_files(files: &mut [FileInfo], sorting: &Sorting) {
     match sorting {
         Sorting::Name => files.sort_by(name),
         Sorting::Ctime => files.sort_by(ctime),
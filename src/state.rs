@@ -1,14 +1,35 @@
-use std::io::{ErrorKind, Result};
-use std::{ffi::OsString, time::SystemTime};
+use std::io::{Error, ErrorKind, Result};
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    ffi::OsString,
+    ops::RangeInclusive,
+    time::SystemTime,
+};
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use tui::widgets::ListState;
 
 use crate::{
-    keybindings::{example, KeyStateMachine},
+    command::CommandLine,
+    config::Config,
+    confirm::{ConfirmDialog, OnConfirm},
+    content_search::ContentResults,
+    events::Spawner,
+    finder::{self, Finder},
+    help::HelpView,
+    input::TextInput,
+    journal::{Change, Journal},
+    keybindings::KeyStateMachine,
+    messages::{MessageHistory, Messages},
+    palette::{self, Palette},
+    preview,
+    rename::RenameMode,
+    search::{self, SearchMode},
     sorting::{sort_files, Sorting},
-    util, CrossTerminal,
+    trash::{self, TrashView},
+    util, wrap, CrossTerminal,
 };
 
 #[derive(Eq, Clone)]
@@ -27,35 +48,106 @@ impl PartialEq for FileInfo {
     }
 }
 
+pub enum Mode {
+    Normal,
+    Search(SearchMode),
+    ContentSearch(TextInput),
+    ContentResults(ContentResults),
+    Finder(Finder),
+    Rename(RenameMode),
+    Confirm(ConfirmDialog),
+    Trash(TrashView),
+    Help(HelpView),
+    Messages(MessageHistory),
+    Command(CommandLine),
+    Palette(Palette),
+}
+
 pub struct State {
+    pub root: PathBuf,
     pub cwd: PathBuf,
     files: Vec<FileInfo>,
     pub list_state: ListState,
+    /// the file shown in the file view, if any
+    pub file_view_path: Option<PathBuf>,
     pub file_view_content: String,
+    /// the first line of the file view that is shown, counted in visual lines if wrapping
+    pub file_view_scroll: usize,
+    /// whether long lines in the file view are wrapped
+    pub wrap: bool,
     pub key_state_machine: KeyStateMachine,
+    pub mode: Mode,
+    /// the other end of the selected range while in visual mode
+    pub visual_anchor: Option<usize>,
+    /// the last filename search, highlighted in the list and used by `n`/`N`
+    pub search_query: Option<String>,
+    /// the commands entered at the `:` prompt, oldest first
+    pub command_history: Vec<String>,
+    /// notes for the footer about what actions did or why they failed
+    pub messages: Messages,
     editor: OsString,
+    /// editor arguments for opening `{file}` at `{line}`
+    open_at_line_templates: Vec<String>,
+    /// names of the actions that ask before doing anything
+    confirm_actions: HashSet<&'static str>,
     sorting: Sorting,
     reverse_sort: bool,
+    last_selections: HashMap<PathBuf, PathBuf>,
+    journal: Journal,
+    /// runs work in the background, see `events`
+    pub spawner: Spawner,
 }
 
 impl State {
-    pub fn new(cwd: PathBuf, editor: OsString, sorting: Sorting, reverse_sort: bool) -> Self {
-        assert!(cwd.is_dir());
+    pub fn new(
+        root: PathBuf,
+        editor: OsString,
+        sorting: Sorting,
+        reverse_sort: bool,
+        config: Config,
+        spawner: Spawner,
+    ) -> Self {
+        assert!(root.is_dir());
+        let open_at_line_templates = config
+            .open_at_line
+            .unwrap_or_else(|| util::default_open_at_line(&editor));
         State {
-            cwd,
+            cwd: root.clone(),
+            root,
             files: Vec::new(),
             list_state: ListState::default(),
+            file_view_path: None,
             file_view_content: String::new(),
-            key_state_machine: KeyStateMachine::new(example::keybindings()),
+            file_view_scroll: 0,
+            wrap: false,
+            key_state_machine: KeyStateMachine::new(config.key_bindings),
+            mode: Mode::Normal,
+            visual_anchor: None,
+            search_query: None,
+            command_history: Vec::new(),
+            messages: Messages::default(),
             editor,
+            open_at_line_templates,
+            confirm_actions: config.confirm_actions,
             sorting,
             reverse_sort,
+            last_selections: HashMap::new(),
+            journal: Journal::default(),
+            spawner,
         }
     }
 
     pub fn update_files(&mut self) -> Result<()> {
+        let selected_path = self.selected_file().map(|f| f.path.clone());
+        let anchor_path = self
+            .visual_anchor
+            .and_then(|anchor| self.files.get(anchor))
+            .map(|f| f.path.clone());
+        let scroll = self.file_view_scroll;
+        let trash_dir = trash::trash_dir(&self.root);
         self.files = std::fs::read_dir(&self.cwd)?
-            .filter_map(|dir_entry| Some(dir_entry.ok()?))
+            .filter_map(|dir_entry| dir_entry.ok())
+            .filter(|dir_entry| dir_entry.path() != trash_dir)
             .map(|dir_entry| -> Result<FileInfo> {
                 let metadata = dir_entry.metadata()?;
                 Ok(FileInfo {
@@ -76,23 +168,67 @@ impl State {
             })
             .filter_map(|r| r.ok())
             .collect();
-        if self.files.is_empty() {
-            self.update_selection(None);
-        } else if let Some(index) = self.list_state.selected() {
-            if index >= self.files.len() {
-                self.update_selection(Some(self.files.len() - 1));
+        sort_files(&mut self.files, &self.sorting);
+        if self.reverse_sort {
+            self.files.reverse();
+        }
+        // the anchor stays on its file too, and visual mode ends if it is gone
+        self.visual_anchor =
+            anchor_path.and_then(|path| self.files.iter().position(|f| f.path == path));
+        // keep the selected file selected, or at least the same position if it is gone
+        let selection = match self.list_state.selected() {
+            Some(_) if self.files.is_empty() => None,
+            Some(index) => selected_path
+                .as_ref()
+                .and_then(|path| self.files.iter().position(|f| &f.path == path))
+                .or(Some(index.min(self.files.len() - 1))),
+            None => None,
+        };
+        self.update_selection(selection);
+        if self.selected_file().map(|f| &f.path) == selected_path.as_ref() {
+            self.file_view_scroll = scroll;
+        }
+        Ok(())
+    }
+
+    /// Called regularly by the main loop. Returns whether anything changed.
+    pub fn tick(&mut self) -> bool {
+        self.messages.expire()
+    }
+
+    /// Rereads the listing and the file view after something changed on disk,
+    /// keeping the selection on the same file.
+    pub fn refresh(&mut self) -> Result<()> {
+        self.update_files()?;
+        match &mut self.mode {
+            Mode::Trash(view) => {
+                view.reload(&self.root)?;
+                trash::update_preview(self);
+                Ok(())
             }
+            // the file view shows a match, not the selected file
+            Mode::ContentResults(_) => Ok(()),
+            _ => self.update_file_view_content(),
         }
+    }
+
+    /// Sorts the files by `sorting` from now on, keeping the selection.
+    pub fn set_sorting(&mut self, sorting: Sorting) {
+        self.sorting = sorting;
         self.update_sort();
-        Ok(())
     }
 
     pub fn update_sort(&mut self) {
         let path = self.selected_file().map(|f| f.path.clone());
+        let anchor_path = self
+            .visual_anchor
+            .map(|anchor| self.files[anchor].path.clone());
         sort_files(&mut self.files, &self.sorting);
         if self.reverse_sort {
             self.files.reverse();
         }
+        self.visual_anchor = anchor_path
+            .and_then(|anchor_path| self.files.iter().position(|f| f.path == anchor_path));
         if let Some(path) = path {
             let new_selection = self
                 .files
@@ -104,13 +240,159 @@ impl State {
     }
 
     pub fn update_file_view_content(&mut self) -> Result<()> {
-        match self.selected_file() {
-            None => self.file_view_content = String::new(),
-            Some(file) => self.file_view_content = std::fs::read_to_string(&file.path)?,
+        self.file_view_path = self.selected_file().map(|file| file.path.clone());
+        // don't keep showing the previous file if reading this one fails
+        self.file_view_content.clear();
+        if let Some(path) = &self.file_view_path {
+            self.file_view_content = preview::load(path)?;
         }
         Ok(())
     }
 
+    /// Opens `path` in the editor with the cursor on `line` and reloads the listing.
+    pub fn open_at_line(
+        &mut self,
+        terminal: &mut CrossTerminal,
+        path: &Path,
+        line: usize,
+    ) -> Result<()> {
+        let args = util::open_at_line_args(&self.open_at_line_templates, path, line);
+        util::open_editor(&self.editor, args, terminal, &self.cwd)?;
+        self.update_files()
+    }
+
+    /// Opens `path` in the editor, which creates it if it is saved, and selects it
+    /// afterwards, changing into its folder if needed. Missing folders are created
+    /// for the editor and removed again if nothing was saved. `path` has to be
+    /// inside the notes root.
+    pub fn edit_note(&mut self, terminal: &mut CrossTerminal, path: PathBuf) -> Result<()> {
+        let folder = path.parent().unwrap_or(&self.root).to_path_buf();
+        if !folder.starts_with(&self.root) {
+            return Err(outside_of_notes(&path));
+        }
+        let existed = path.symlink_metadata().is_ok();
+        // deepest first
+        let missing_folders: Vec<&Path> = folder
+            .ancestors()
+            .take_while(|ancestor| !ancestor.exists())
+            .collect();
+        std::fs::create_dir_all(&folder)?;
+        let result = util::open_editor(&self.editor, vec![&path], terminal, &self.cwd);
+        let exists = path.symlink_metadata().is_ok();
+        if !exists {
+            for missing in &missing_folders {
+                // fails if something else has been put there in the meantime
+                let _ = std::fs::remove_dir(missing);
+            }
+        }
+        result?;
+        if !existed && exists {
+            self.journal.record(vec![Change::created(path.clone())]);
+        }
+        if folder == self.cwd || !exists {
+            self.update_files()?;
+        } else {
+            self.change_dir(folder)?;
+        }
+        let index = self.files.iter().position(|f| f.path == path);
+        if let Some(index) = index {
+            self.update_selection(Some(index));
+        }
+        self.update_file_view_content()
+    }
+
+    /// Runs `on_confirm` right away, or only after asking if confirmation is
+    /// configured for `action_name`.
+    pub fn confirm_then(
+        &mut self,
+        terminal: &mut CrossTerminal,
+        action_name: &str,
+        title: String,
+        items: Vec<String>,
+        on_confirm: OnConfirm,
+    ) -> Result<()> {
+        if self.confirm_actions.contains(action_name) {
+            let previous = std::mem::replace(&mut self.mode, Mode::Normal);
+            self.mode = Mode::Confirm(ConfirmDialog::new(title, items, on_confirm, previous));
+            Ok(())
+        } else {
+            on_confirm(self, terminal)
+        }
+    }
+
+    /// Renames the file at `from` within its folder and keeps it selected. Existing
+    /// files are never overwritten.
+    pub fn rename_file(&mut self, from: PathBuf, new_name: &str) -> Result<()> {
+        if new_name.is_empty() || new_name == "." || new_name == ".." || new_name.contains('/') {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("invalid file name \"{}\"", new_name),
+            ));
+        }
+        if from.symlink_metadata().is_err() {
+            return Err(Error::new(ErrorKind::NotFound, "file no longer exists"));
+        }
+        let to = from.with_file_name(new_name);
+        if to == from {
+            return Ok(());
+        }
+        if to.symlink_metadata().is_ok() {
+            return Err(Error::new(
+                ErrorKind::AlreadyExists,
+                format!("\"{}\" already exists", new_name),
+            ));
+        }
+        std::fs::rename(&from, &to)?;
+        self.journal.record(vec![Change::renamed(from, to.clone())]);
+        self.update_files()?;
+        let index = self.files.iter().position(|f| f.path == to);
+        self.select(index)
+    }
+
+    /// Changes into `dir`, which has to be inside the notes root. The selection is
+    /// restored to whatever was selected the last time `dir` was left.
+    pub fn change_dir(&mut self, dir: PathBuf) -> Result<()> {
+        if !dir.starts_with(&self.root) {
+            return Err(outside_of_notes(&dir));
+        }
+        let selected_path = self.selected_file().map(|file| file.path.clone());
+        let previous_cwd = std::mem::replace(&mut self.cwd, dir);
+        let previous_selection = self.list_state.selected();
+        let previous_anchor = self.visual_anchor.take();
+        self.list_state.select(None);
+        // stay where we are if the new folder can't be read
+        if let Err(e) = self.update_files() {
+            self.cwd = previous_cwd;
+            self.list_state.select(previous_selection);
+            self.visual_anchor = previous_anchor;
+            return Err(e);
+        }
+        if let Some(path) = selected_path {
+            self.last_selections.insert(previous_cwd, path);
+        }
+        let previous = self
+            .last_selections
+            .get(&self.cwd)
+            .and_then(|path| self.files.iter().position(|f| &f.path == path));
+        match previous {
+            Some(index) => self.update_selection(Some(index)),
+            None if !self.files.is_empty() => self.update_selection(Some(0)),
+            None => {}
+        }
+        self.update_file_view_content()
+    }
+
+    /// The current directory relative to the notes root, including the root's name.
+    pub fn relative_cwd(&self) -> PathBuf {
+        let root_name = self.root.file_name().unwrap_or(self.root.as_os_str());
+        let mut path = PathBuf::from(root_name);
+        let relative = self.cwd.strip_prefix(&self.root).unwrap();
+        if !relative.as_os_str().is_empty() {
+            path.push(relative);
+        }
+        path
+    }
+
     pub fn selected_file(&self) -> Option<&FileInfo> {
         self.list_state.selected().map(|index| {
             assert!(index < self.files.len());
@@ -118,20 +400,66 @@ impl State {
         })
     }
 
-    pub fn file_names(&self) -> Vec<&str> {
+    /// The files between the visual anchor and the selection, if in visual mode.
+    pub fn visual_range(&self) -> Option<RangeInclusive<usize>> {
+        let anchor = self.visual_anchor?;
+        let selected = self.list_state.selected()?;
+        Some(anchor.min(selected)..=anchor.max(selected))
+    }
+
+    pub fn files(&self) -> &[FileInfo] {
+        &self.files
+    }
+
+    pub fn file_names(&self) -> Vec<Cow<'_, str>> {
         self.files
             .iter()
-            .filter_map(|f| f.path.file_name()?.to_str())
+            .map(|f| f.name.to_string_lossy())
             .collect()
     }
 
     pub fn update_selection(&mut self, index: Option<usize>) {
-        assert!(!self.files.is_empty() || index == None);
+        assert!(!self.files.is_empty() || index.is_none());
         if let Some(i) = index {
             assert!(i < self.files.len());
         }
+        if index != self.list_state.selected() {
+            self.file_view_scroll = 0;
+        }
         self.list_state.select(index);
     }
+
+    /// The number of lines in the file view when it is `width` columns wide.
+    pub fn file_view_line_count(&self, width: usize) -> usize {
+        if self.wrap {
+            wrap::visual_line_count(&self.file_view_content, width)
+        } else {
+            self.file_view_content.lines().count()
+        }
+    }
+
+    /// Scrolls the file view by `lines`, at most until its last line is at the top.
+    /// With wrapping, these are visual lines in a file view `width` columns wide.
+    pub fn scroll_file_view(&mut self, lines: isize, width: usize) {
+        let last_line = self.file_view_line_count(width).saturating_sub(1);
+        self.file_view_scroll = self
+            .file_view_scroll
+            .saturating_add_signed(lines)
+            .min(last_line);
+    }
+
+    /// Selects `index` and shows it in the file view.
+    pub fn select(&mut self, index: Option<usize>) -> Result<()> {
+        self.update_selection(index);
+        self.update_file_view_content()
+    }
+}
+
+fn outside_of_notes(path: &Path) -> Error {
+    Error::new(
+        ErrorKind::InvalidInput,
+        format!("\"{}\" is outside of the notes", path.display()),
+    )
 }
 
 pub mod updates {
@@ -192,11 +520,7 @@ pub mod updates {
         }
         let last_index = state.files.len() - 1;
         let count = if count == 0 { 0 } else { count - 1 };
-        let new = if count > last_index {
-            0
-        } else {
-            last_index - count
-        };
+        let new = last_index.saturating_sub(count);
         state.update_selection(Some(new));
         state.update_file_view_content()
     }
@@ -206,18 +530,12 @@ pub mod updates {
         terminal: &mut CrossTerminal,
         offset: i64,
     ) -> Result<()> {
-        let filename = util::format_date(offset);
+        let filename = util::format_date(offset)
+            .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "date out of range"))?;
         let mut path = state.cwd.clone();
         path.push(filename);
         path.set_extension("md");
-        util::open_editor(&state.editor, vec![&path], terminal, &state.cwd)?;
-        state.update_files()?;
-        let index = state.files.iter().position(|f| f.path == path);
-        if let Some(index) = index {
-            state.update_selection(Some(index));
-        }
-        state.update_file_view_content()?;
-        Ok(())
+        state.edit_note(terminal, path)
     }
 
     pub fn open_rel_date_fwd(
@@ -237,7 +555,25 @@ pub mod updates {
     }
 
     pub fn open_selected(state: &mut State, terminal: &mut CrossTerminal, _: usize) -> Result<()> {
+        if let Some(range) = state.visual_range() {
+            state.visual_anchor = None;
+            let paths: Vec<&PathBuf> = state.files[range]
+                .iter()
+                .filter(|f| !f.is_folder)
+                .map(|f| &f.path)
+                .collect();
+            if !paths.is_empty() {
+                util::open_editor(&state.editor, paths, terminal, &state.cwd)?;
+                state.update_files()?;
+                state.update_file_view_content()?;
+            }
+            return Ok(());
+        }
         if let Some(file) = state.selected_file() {
+            if file.is_folder {
+                let path = file.path.clone();
+                return state.change_dir(path);
+            }
             let path = file.path.clone();
             util::open_editor(&state.editor, vec![&file.path], terminal, &state.cwd)?;
             state.update_files()?;
@@ -250,6 +586,17 @@ pub mod updates {
         Ok(())
     }
 
+    pub fn leave_folder(state: &mut State, _: &mut CrossTerminal, _: usize) -> Result<()> {
+        if state.cwd == state.root {
+            return Ok(());
+        }
+        let parent = state.cwd.parent().unwrap().to_path_buf();
+        state
+            .last_selections
+            .insert(parent.clone(), state.cwd.clone());
+        state.change_dir(parent)
+    }
+
     pub fn sort_by_name(state: &mut State, _: &mut CrossTerminal, _: usize) -> Result<()> {
         state.sorting = Sorting::Name;
         state.update_sort();
@@ -281,9 +628,12 @@ pub mod updates {
     }
 
     pub fn reverse_sort(state: &mut State, _: &mut CrossTerminal, _: usize) -> Result<()> {
-        let f = state.selected_file().map(FileInfo::clone);
+        let f = state.selected_file().cloned();
         state.reverse_sort = !state.reverse_sort;
         state.files.reverse();
+        state.visual_anchor = state
+            .visual_anchor
+            .map(|anchor| state.files.len() - 1 - anchor);
         if let Some(f) = f {
             let new_selection = state.files.iter().position(|other| *other == f).unwrap();
             state.update_selection(Some(new_selection));
@@ -291,24 +641,294 @@ pub mod updates {
         Ok(())
     }
 
-    pub fn delete_file(state: &mut State, _: &mut CrossTerminal, count: usize) -> Result<()> {
-        let count = if count == 0 { 1 } else { count };
-        for _ in 0..count {
-            if let Some(index) = state.list_state.selected() {
-                let file = &state.files[index];
-                std::fs::remove_file(&file.path)?;
-                state.files.remove(index);
-                if state.files.is_empty() {
-                    state.update_selection(None);
-                    break;
+    pub fn start_search(state: &mut State, _: &mut CrossTerminal, _: usize) -> Result<()> {
+        let previous_query = state.search_query.clone();
+        let previous_selection = state.selected_file().map(|f| f.path.clone());
+        state.mode = Mode::Search(SearchMode::new(previous_selection, previous_query));
+        Ok(())
+    }
+
+    pub fn start_content_search(state: &mut State, _: &mut CrossTerminal, _: usize) -> Result<()> {
+        state.mode = Mode::ContentSearch(TextInput::new(""));
+        Ok(())
+    }
+
+    pub fn open_finder(state: &mut State, _: &mut CrossTerminal, _: usize) -> Result<()> {
+        state.mode = Mode::Finder(finder::new(state));
+        Ok(())
+    }
+
+    fn jump_to_match(state: &mut State, count: usize, forward: bool) -> Result<()> {
+        let matches = match &state.search_query {
+            Some(query) => search::matching_indices(state, query),
+            None => return Ok(()),
+        };
+        let mut index = state.list_state.selected();
+        for _ in 0..count.max(1) {
+            index = search::next_match(&matches, index, forward);
+        }
+        if index.is_some() {
+            state.select(index)?;
+        }
+        Ok(())
+    }
+
+    pub fn search_next(state: &mut State, _: &mut CrossTerminal, count: usize) -> Result<()> {
+        jump_to_match(state, count, true)
+    }
+
+    pub fn search_previous(state: &mut State, _: &mut CrossTerminal, count: usize) -> Result<()> {
+        jump_to_match(state, count, false)
+    }
+
+    fn start_rename(state: &mut State, after_extension: bool) -> Result<()> {
+        state.visual_anchor = None;
+        if let Some(index) = state.list_state.selected() {
+            state.mode = Mode::Rename(RenameMode::new(state, index, after_extension));
+        }
+        Ok(())
+    }
+
+    pub fn rename(state: &mut State, _: &mut CrossTerminal, _: usize) -> Result<()> {
+        start_rename(state, false)
+    }
+
+    pub fn rename_after_extension(
+        state: &mut State,
+        _: &mut CrossTerminal,
+        _: usize,
+    ) -> Result<()> {
+        start_rename(state, true)
+    }
+
+    pub fn toggle_visual(state: &mut State, _: &mut CrossTerminal, _: usize) -> Result<()> {
+        state.visual_anchor = match state.visual_anchor {
+            Some(_) => None,
+            None => state.list_state.selected(),
+        };
+        Ok(())
+    }
+
+    /// The visual range, or else `count` files starting at the selection.
+    fn target_range(state: &State, count: usize) -> Option<RangeInclusive<usize>> {
+        if let Some(range) = state.visual_range() {
+            return Some(range);
+        }
+        let index = state.list_state.selected()?;
+        Some(index..=(index + count.max(1) - 1).min(state.files.len() - 1))
+    }
+
+    pub fn yank_paths(state: &mut State, _: &mut CrossTerminal, _: usize) -> Result<()> {
+        let range = match target_range(state, 1) {
+            Some(range) => range,
+            None => return Ok(()),
+        };
+        state.visual_anchor = None;
+        let paths: Vec<Cow<str>> = state.files[range]
+            .iter()
+            .map(|f| f.path.to_string_lossy())
+            .collect();
+        util::copy_to_clipboard(&paths.join("\n"))
+    }
+
+    pub fn delete_file(
+        state: &mut State,
+        terminal: &mut CrossTerminal,
+        count: usize,
+    ) -> Result<()> {
+        let range = match target_range(state, count) {
+            Some(range) => range,
+            None => return Ok(()),
+        };
+        state.visual_anchor = None;
+        let start = *range.start();
+        let files = state.files[range].to_vec();
+        let title = match files.len() {
+            1 => "Move 1 file to the trash?".to_string(),
+            n => format!("Move {} files to the trash?", n),
+        };
+        let items = files
+            .iter()
+            .map(|f| f.name.to_string_lossy().into_owned())
+            .collect();
+        state.confirm_then(
+            terminal,
+            "file.delete",
+            title,
+            items,
+            Box::new(move |state, _| {
+                let mut operation = Vec::new();
+                let result: Result<()> = files.iter().try_for_each(|file| {
+                    operation.push(Change::trashed(trash::trash(&state.root, &file.path)?));
+                    Ok(())
+                });
+                state.journal.record(operation);
+                result?;
+                state.update_files()?;
+                let selection = match state.files.len() {
+                    0 => None,
+                    len => Some(start.min(len - 1)),
+                };
+                state.select(selection)
+            }),
+        )
+    }
+
+    pub fn show_help(state: &mut State, _: &mut CrossTerminal, _: usize) -> Result<()> {
+        state.mode = Mode::Help(HelpView::new());
+        Ok(())
+    }
+
+    pub fn open_palette(state: &mut State, _: &mut CrossTerminal, _: usize) -> Result<()> {
+        state.mode = Mode::Palette(palette::new(state));
+        Ok(())
+    }
+
+    pub fn start_command(state: &mut State, _: &mut CrossTerminal, _: usize) -> Result<()> {
+        state.mode = Mode::Command(CommandLine::new());
+        Ok(())
+    }
+
+    pub fn show_messages(state: &mut State, _: &mut CrossTerminal, _: usize) -> Result<()> {
+        state.mode = Mode::Messages(MessageHistory::new(&state.messages));
+        Ok(())
+    }
+
+    /// The width and height of the file view.
+    fn file_view_size(terminal: &CrossTerminal) -> Result<(usize, usize)> {
+        let (_, h_chunks) = crate::layout(terminal.size()?);
+        Ok((h_chunks[2].width as usize, h_chunks[2].height as usize))
+    }
+
+    pub fn scroll_preview_down(
+        state: &mut State,
+        terminal: &mut CrossTerminal,
+        count: usize,
+    ) -> Result<()> {
+        let (width, _) = file_view_size(terminal)?;
+        state.scroll_file_view(count.max(1) as isize, width);
+        Ok(())
+    }
+
+    pub fn scroll_preview_up(
+        state: &mut State,
+        terminal: &mut CrossTerminal,
+        count: usize,
+    ) -> Result<()> {
+        let (width, _) = file_view_size(terminal)?;
+        state.scroll_file_view(-(count.max(1) as isize), width);
+        Ok(())
+    }
+
+    pub fn scroll_preview_half_page_down(
+        state: &mut State,
+        terminal: &mut CrossTerminal,
+        _: usize,
+    ) -> Result<()> {
+        let (width, height) = file_view_size(terminal)?;
+        state.scroll_file_view((height / 2).max(1) as isize, width);
+        Ok(())
+    }
+
+    pub fn scroll_preview_half_page_up(
+        state: &mut State,
+        terminal: &mut CrossTerminal,
+        _: usize,
+    ) -> Result<()> {
+        let (width, height) = file_view_size(terminal)?;
+        state.scroll_file_view(-((height / 2).max(1) as isize), width);
+        Ok(())
+    }
+
+    /// Toggles wrapping in the file view, keeping the same line at the top.
+    pub fn toggle_wrap(state: &mut State, terminal: &mut CrossTerminal, _: usize) -> Result<()> {
+        let (width, _) = file_view_size(terminal)?;
+        let content = &state.file_view_content;
+        state.file_view_scroll = if state.wrap {
+            wrap::source_line_of(content, state.file_view_scroll, width)
+        } else {
+            wrap::visual_line_of(content, state.file_view_scroll, width)
+        };
+        state.wrap = !state.wrap;
+        Ok(())
+    }
+
+    pub fn open_trash(state: &mut State, _: &mut CrossTerminal, _: usize) -> Result<()> {
+        state.visual_anchor = None;
+        state.mode = Mode::Trash(TrashView::new(&state.root)?);
+        trash::update_preview(state);
+        Ok(())
+    }
+
+    pub fn restore_from_trash(state: &mut State, _: &mut CrossTerminal, _: usize) -> Result<()> {
+        let Mode::Trash(view) = &mut state.mode else {
+            return Ok(());
+        };
+        if let Some(entry) = view.selected() {
+            trash::restore(entry)?;
+            let change = Change::restored(entry.original_path.clone());
+            state.journal.record(vec![change]);
+            view.reload(&state.root)?;
+            state.update_files()?;
+            trash::update_preview(state);
+        }
+        Ok(())
+    }
+
+    pub fn purge_from_trash(
+        state: &mut State,
+        terminal: &mut CrossTerminal,
+        _: usize,
+    ) -> Result<()> {
+        let Mode::Trash(view) = &state.mode else {
+            return Ok(());
+        };
+        let Some(entry) = view.selected().cloned() else {
+            return Ok(());
+        };
+        let name = entry.path.file_name().unwrap_or_default();
+        let items = vec![name.to_string_lossy().into_owned()];
+        state.confirm_then(
+            terminal,
+            "trash.purge",
+            "Delete permanently?".to_string(),
+            items,
+            Box::new(move |state, _| {
+                trash::purge(&entry)?;
+                if let Mode::Trash(view) = &mut state.mode {
+                    view.reload(&state.root)?;
                 }
-                if index >= state.files.len() {
-                    state.update_selection(Some(state.files.len() - 1));
+                trash::update_preview(state);
+                Ok(())
+            }),
+        )
+    }
+
+    pub fn undo(state: &mut State, _: &mut CrossTerminal, count: usize) -> Result<()> {
+        for _ in 0..count.max(1) {
+            match state.journal.undo(&state.root) {
+                Ok(description) => state.messages.info(format!("undone: {}", description)),
+                Err(e) => {
+                    state.messages.warning(format!("can't undo: {}", e));
+                    break;
                 }
             }
         }
-        state.update_file_view_content()?;
+        state.update_files()?;
+        state.update_file_view_content()
+    }
 
-        Ok(())
+    pub fn redo(state: &mut State, _: &mut CrossTerminal, count: usize) -> Result<()> {
+        for _ in 0..count.max(1) {
+            match state.journal.redo(&state.root) {
+                Ok(description) => state.messages.info(format!("redone: {}", description)),
+                Err(e) => {
+                    state.messages.warning(format!("can't redo: {}", e));
+                    break;
+                }
+            }
+        }
+        state.update_files()?;
+        state.update_file_view_content()
     }
 }
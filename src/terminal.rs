@@ -0,0 +1,58 @@
+//! Setting up the terminal and, no matter how the app ends, restoring it.
+
+use std::{backtrace::Backtrace, io, panic, thread};
+
+use crossterm::{
+    cursor::Show,
+    event::{DisableMouseCapture, EnableMouseCapture},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+
+/// Keeps the terminal in raw mode on the alternate screen with mouse capture
+/// until it is dropped, including when returning early with an error.
+pub struct TerminalGuard;
+
+impl TerminalGuard {
+    pub fn new() -> io::Result<Self> {
+        enable_raw_mode()?;
+        // from here on, dropping the guard undoes whatever has been done
+        let guard = TerminalGuard;
+        execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+        Ok(guard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore();
+    }
+}
+
+/// Leaves the alternate screen and raw mode. Errors are ignored, as this also
+/// runs when things have already gone wrong.
+pub fn restore() {
+    let _ = execute!(
+        io::stdout(),
+        DisableMouseCapture,
+        LeaveAlternateScreen,
+        Show
+    );
+    let _ = disable_raw_mode();
+}
+
+/// Writes a crash report to the log. For panics on the main thread, which end the
+/// app, the terminal is restored before the message is printed, so that it can be
+/// read. Other threads report theirs in the footer, see `events::catch_panic`.
+/// Has to be called on the main thread.
+pub fn install_panic_hook() {
+    let main_thread = thread::current().id();
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        simple_log::log::error!("crashed: {}\n{}", info, Backtrace::force_capture());
+        if thread::current().id() == main_thread {
+            restore();
+            default_hook(info);
+        }
+    }));
+}
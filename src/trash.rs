@@ -0,0 +1,308 @@
+//! A trash inside the notes root, laid out like the XDG trash: trashed files are
+//! moved to `.trash/files` and their original path and deletion date are kept
+//! in a `.trashinfo` file of the same name in `.trash/info`.
+
+use std::{
+    borrow::Cow,
+    ffi::OsString,
+    io::{Error, ErrorKind, Result, Write},
+    path::{Path, PathBuf},
+};
+
+use chrono::Local;
+use crossterm::event::{KeyCode, KeyEvent};
+use tui::widgets::ListState;
+
+use crate::{
+    actions, palette, preview,
+    state::{Mode, State},
+    CrossTerminal,
+};
+
+#[derive(Clone)]
+pub struct TrashEntry {
+    /// where the file is now, inside the trash
+    pub path: PathBuf,
+    pub original_path: PathBuf,
+    pub deletion_date: String,
+    info_path: PathBuf,
+}
+
+/// The trash view, which replaces the file list until it is left with Esc.
+pub struct TrashView {
+    pub entries: Vec<TrashEntry>,
+    pub list_state: ListState,
+}
+
+impl TrashView {
+    pub fn new(root: &Path) -> Result<Self> {
+        let mut view = TrashView {
+            entries: Vec::new(),
+            list_state: ListState::default(),
+        };
+        view.reload(root)?;
+        Ok(view)
+    }
+
+    /// Rereads the trash, keeping the selection at the same position.
+    pub fn reload(&mut self, root: &Path) -> Result<()> {
+        self.entries = list(root)?;
+        let selection = match self.entries.len() {
+            0 => None,
+            len => Some(self.list_state.selected().unwrap_or(0).min(len - 1)),
+        };
+        self.list_state.select(selection);
+        Ok(())
+    }
+
+    pub fn selected(&self) -> Option<&TrashEntry> {
+        self.list_state.selected().map(|i| &self.entries[i])
+    }
+}
+
+pub fn trash_dir(root: &Path) -> PathBuf {
+    root.join(".trash")
+}
+
+/// Moves `path` into the trash and returns where it ended up.
+pub fn trash(root: &Path, path: &Path) -> Result<TrashEntry> {
+    let trash = trash_dir(root);
+    let (files, info) = (trash.join("files"), trash.join("info"));
+    std::fs::create_dir_all(&files)?;
+    std::fs::create_dir_all(&info)?;
+
+    let name = path
+        .file_name()
+        .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "can't trash a path without a name"))?;
+    // claim a unique name by creating its info file, like other XDG implementations do
+    let mut suffix = 1;
+    let (trashed_name, info_path, mut info_file) = loop {
+        let mut candidate = OsString::from(name);
+        if suffix > 1 {
+            candidate.push(format!(".{}", suffix));
+        }
+        let mut info_name = candidate.clone();
+        info_name.push(".trashinfo");
+        let info_path = info.join(info_name);
+        match std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&info_path)
+        {
+            Ok(file) if !files.join(&candidate).exists() => break (candidate, info_path, file),
+            Ok(_) => std::fs::remove_file(&info_path)?,
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => {}
+            Err(e) => return Err(e),
+        }
+        suffix += 1;
+    };
+
+    let deletion_date = Local::now().format("%Y-%m-%dT%H:%M:%S").to_string();
+    let original_path = std::path::absolute(path)?;
+    write!(
+        info_file,
+        "[Trash Info]\nPath={}\nDeletionDate={}\n",
+        encode_path(&original_path),
+        deletion_date
+    )?;
+    let trashed_path = files.join(trashed_name);
+    if let Err(e) = std::fs::rename(path, &trashed_path) {
+        std::fs::remove_file(&info_path)?;
+        return Err(e);
+    }
+    Ok(TrashEntry {
+        path: trashed_path,
+        original_path,
+        deletion_date,
+        info_path,
+    })
+}
+
+/// All entries in the trash, most recently deleted first. Entries without a
+/// readable info file are left out.
+pub fn list(root: &Path) -> Result<Vec<TrashEntry>> {
+    let trash = trash_dir(root);
+    let info = match std::fs::read_dir(trash.join("info")) {
+        Ok(info) => info,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut entries: Vec<TrashEntry> = info
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let info_path = entry.path();
+            // names don't have to be UTF-8
+            let info_name = path_bytes(Path::new(info_path.file_name()?));
+            let name = path_from_bytes(info_name.strip_suffix(b".trashinfo")?.to_vec());
+            let path = trash.join("files").join(name);
+            let text = std::fs::read_to_string(&info_path).ok()?;
+            let mut original_path = None;
+            let mut deletion_date = String::new();
+            for line in text.lines() {
+                if let Some(value) = line.strip_prefix("Path=") {
+                    original_path = Some(decode_path(value));
+                } else if let Some(value) = line.strip_prefix("DeletionDate=") {
+                    deletion_date = value.to_string();
+                }
+            }
+            Some(TrashEntry {
+                path,
+                original_path: original_path?,
+                deletion_date,
+                info_path,
+            })
+        })
+        .collect();
+    entries.sort_by(|e1, e2| e2.deletion_date.cmp(&e1.deletion_date));
+    Ok(entries)
+}
+
+/// Moves `entry` back to where it was deleted from. Refuses to overwrite a file
+/// that has been created there in the meantime.
+pub fn restore(entry: &TrashEntry) -> Result<()> {
+    if entry.original_path.symlink_metadata().is_ok() {
+        return Err(Error::new(
+            ErrorKind::AlreadyExists,
+            format!("{} already exists", entry.original_path.display()),
+        ));
+    }
+    if let Some(parent) = entry.original_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::rename(&entry.path, &entry.original_path)?;
+    std::fs::remove_file(&entry.info_path)
+}
+
+/// Deletes `entry` for good.
+pub fn purge(entry: &TrashEntry) -> Result<()> {
+    match entry.path.symlink_metadata() {
+        Ok(metadata) if metadata.is_dir() => std::fs::remove_dir_all(&entry.path)?,
+        Ok(_) => std::fs::remove_file(&entry.path)?,
+        Err(e) if e.kind() == ErrorKind::NotFound => {}
+        Err(e) => return Err(e),
+    }
+    std::fs::remove_file(&entry.info_path)
+}
+
+/// Percent-encodes everything but unreserved characters and `/`, as the spec asks.
+fn encode_path(path: &Path) -> String {
+    let mut encoded = String::new();
+    for b in path_bytes(path).iter() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                encoded.push(*b as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    encoded
+}
+
+fn decode_path(encoded: &str) -> PathBuf {
+    let bytes = encoded.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(b)) => {
+                decoded.push(b);
+                i += 3;
+            }
+            (b, _) => {
+                decoded.push(b);
+                i += 1;
+            }
+        }
+    }
+    path_from_bytes(decoded)
+}
+
+#[cfg(unix)]
+fn path_bytes(path: &Path) -> Cow<'_, [u8]> {
+    use std::os::unix::ffi::OsStrExt;
+    Cow::Borrowed(path.as_os_str().as_bytes())
+}
+
+/// Elsewhere paths aren't bytes, so they are written as UTF-8, which is lossy for
+/// the rare paths that aren't valid Unicode.
+#[cfg(not(unix))]
+fn path_bytes(path: &Path) -> Cow<'_, [u8]> {
+    Cow::Owned(path.to_string_lossy().into_owned().into_bytes())
+}
+
+#[cfg(unix)]
+fn path_from_bytes(bytes: Vec<u8>) -> PathBuf {
+    use std::os::unix::ffi::OsStringExt;
+    PathBuf::from(OsString::from_vec(bytes))
+}
+
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: Vec<u8>) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// The actions the trash view runs when a key bound to them alone is pressed,
+/// with what the header calls them.
+pub const VIEW_ACTIONS: &[(&str, &str)] = &[
+    ("trash.restore", "restore"),
+    ("trash.purge", "delete permanently"),
+];
+
+/// The keys of the view's actions for the header, e.g. `r: restore  x, D: delete
+/// permanently`.
+pub fn key_hints(state: &State) -> String {
+    VIEW_ACTIONS
+        .iter()
+        .filter_map(|(name, hint)| {
+            let keys = palette::keys(state, actions::by_name(name)?);
+            (!keys.is_empty()).then(|| format!("{}: {}", keys, hint))
+        })
+        .collect::<Vec<String>>()
+        .join("  ")
+}
+
+pub fn handle_key(state: &mut State, terminal: &mut CrossTerminal, key: KeyEvent) -> Result<()> {
+    let Mode::Trash(view) = &mut state.mode else {
+        return Ok(());
+    };
+    let action = state
+        .key_state_machine
+        .single_key_actions(key)
+        .find(|action| VIEW_ACTIONS.iter().any(|(name, _)| *name == action.name));
+    if let Some(action) = action {
+        return (action.function)(state, terminal, 0);
+    }
+    let len = view.entries.len();
+    let selected = view.list_state.selected();
+    let new_selection = match key.code {
+        KeyCode::Char('j') | KeyCode::Down => selected.map(|i| (i + 1).min(len - 1)),
+        KeyCode::Char('k') | KeyCode::Up => selected.map(|i| i.saturating_sub(1)),
+        KeyCode::Char('g') | KeyCode::Home => selected.map(|_| 0),
+        KeyCode::Char('G') | KeyCode::End => selected.map(|_| len - 1),
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('h') => {
+            state.mode = Mode::Normal;
+            return state.update_file_view_content();
+        }
+        _ => return Ok(()),
+    };
+    view.list_state.select(new_selection);
+    update_preview(state);
+    Ok(())
+}
+
+/// Shows the selected trashed file in the file view.
+pub fn update_preview(state: &mut State) {
+    let Mode::Trash(view) = &state.mode else {
+        return;
+    };
+    state.file_view_scroll = 0;
+    state.file_view_path = view.selected().map(|entry| entry.path.clone());
+    state.file_view_content = match &state.file_view_path {
+        Some(path) => preview::load(path).unwrap_or_default(),
+        None => String::new(),
+    };
+}
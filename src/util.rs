@@ -3,10 +3,11 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use chrono::{Duration, Local};
+use chrono::{Duration, Local, NaiveDate};
 use crossterm::execute;
+use std::io::Write;
 
-use crate::CrossTerminal;
+use crate::{events, CrossTerminal};
 
 pub fn fail<T, S: AsRef<str>>(msg: S) -> T {
     eprintln!("{}", msg.as_ref());
@@ -34,8 +35,8 @@ pub fn init_logging() -> std::io::Result<()> {
 
 fn is_vim(editor: &OsStr) -> bool {
     let path = PathBuf::from(editor);
-    let filename = path.file_name().unwrap();
-    return filename == "nvim" || filename == "vim";
+    path.file_name()
+        .is_some_and(|filename| filename == "nvim" || filename == "vim")
 }
 
 pub fn open_editor<I, S>(
@@ -48,25 +49,153 @@ where
     I: IntoIterator<Item = S>,
     S: AsRef<OsStr>,
 {
-    execute!(std::io::stdout(), crossterm::terminal::LeaveAlternateScreen)?;
+    execute!(
+        std::io::stdout(),
+        crossterm::event::DisableMouseCapture,
+        crossterm::terminal::LeaveAlternateScreen
+    )?;
+    // the editor needs all input for itself
+    events::pause_input();
+    let mut command = std::process::Command::new(editor);
     // ugly hack for changing vim's working directory
     if is_vim(editor) {
         let mut cd_prompt = OsString::from("cd ");
         cd_prompt.push(working_directory.as_os_str());
-        std::process::Command::new(editor)
-            .arg("-c")
-            .arg(cd_prompt)
-            .args(args)
-            .status()?;
-    } else {
-        std::process::Command::new(editor).args(args).status()?;
+        command.arg("-c").arg(cd_prompt);
     }
-    execute!(std::io::stdout(), crossterm::terminal::EnterAlternateScreen)?;
+    let status = command.args(args).status();
+    events::resume_input();
+    // come back even if the editor couldn't be started, as the app goes on
+    execute!(
+        std::io::stdout(),
+        crossterm::terminal::EnterAlternateScreen,
+        crossterm::event::EnableMouseCapture
+    )?;
     terminal.clear()?;
+    status?;
     Ok(())
 }
 
-pub fn format_date(offset: i64) -> String {
-    let now = Local::now() + Duration::days(offset);
-    format!("{}", now.format("%Y-%m-%d"))
+/// Editors known to open a file at a line given as `+LINE` in front of it.
+const PLUS_LINE_EDITORS: &[&str] = &[
+    "vi",
+    "vim",
+    "nvim",
+    "gvim",
+    "nano",
+    "emacs",
+    "emacsclient",
+    "kak",
+    "micro",
+];
+
+/// The templates for opening a file at a line when none are configured: with
+/// `+{line}` for the editors known to understand it, otherwise just the file.
+pub fn default_open_at_line(editor: &OsStr) -> Vec<String> {
+    let name = Path::new(editor).file_name().and_then(OsStr::to_str);
+    if name.is_some_and(|name| PLUS_LINE_EDITORS.contains(&name)) {
+        vec!["+{line}".to_string(), "{file}".to_string()]
+    } else {
+        vec!["{file}".to_string()]
+    }
+}
+
+/// Builds the editor arguments for opening `file` at `line` from templates
+/// containing `{file}` and `{line}`.
+pub fn open_at_line_args(templates: &[String], file: &Path, line: usize) -> Vec<OsString> {
+    templates
+        .iter()
+        .map(|template| {
+            let line = template.replace("{line}", &line.to_string());
+            let mut arg = OsString::new();
+            let mut parts = line.split("{file}");
+            arg.push(parts.next().unwrap_or_default());
+            for part in parts {
+                arg.push(file.as_os_str());
+                arg.push(part);
+            }
+            arg
+        })
+        .collect()
+}
+
+/// Guesses whether `bytes` are not meant to be read as text by looking at their
+/// start: it contains a NUL byte, or many control characters that text doesn't use.
+pub fn is_binary(bytes: &[u8]) -> bool {
+    let start = &bytes[..bytes.len().min(8192)];
+    if start.contains(&0) {
+        return true;
+    }
+    let control = start
+        .iter()
+        .filter(|&&b| b < 0x20 && !matches!(b, b'\n' | b'\r' | b'\t' | 0x0c | 0x1b) || b == 0x7f)
+        .count();
+    control * 10 > start.len()
+}
+
+/// Recursively lists all files below `dir`, skipping hidden files and folders.
+pub fn walk_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut dirs = vec![dir.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            match entry.file_type() {
+                Ok(t) if t.is_dir() => dirs.push(entry.path()),
+                Ok(_) => files.push(entry.path()),
+                Err(_) => {}
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+/// Puts `text` into the system clipboard with the OSC 52 escape sequence, which
+/// most terminals support, even over ssh.
+pub fn copy_to_clipboard(text: &str) -> std::io::Result<()> {
+    let mut stdout = std::io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", base64(text.as_bytes()))?;
+    stdout.flush()
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, b)| n | (*b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// The date `offset` days from today, unless that is out of the range of dates.
+pub fn date_from_today(offset: i64) -> Option<NaiveDate> {
+    // dates only span a few hundred thousand years, and `Duration::days` panics
+    // for offsets far beyond that
+    if offset.unsigned_abs() > 1_000_000_000 {
+        return None;
+    }
+    Local::now()
+        .date_naive()
+        .checked_add_signed(Duration::days(offset))
+}
+
+pub fn format_date(offset: i64) -> Option<String> {
+    Some(date_from_today(offset)?.format("%Y-%m-%d").to_string())
 }
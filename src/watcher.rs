@@ -0,0 +1,38 @@
+//! Watches the notes root for changes made by other programs.
+
+use std::path::Path;
+
+use notify::{event::ModifyKind, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::events::catch_panic;
+
+/// Calls `on_change` whenever something below `root` changes. If that panics,
+/// `on_panic` is called with the message and watching goes on. The watcher stops
+/// when it is dropped.
+pub fn watch<F, P>(root: &Path, on_change: F, on_panic: P) -> notify::Result<RecommendedWatcher>
+where
+    F: Fn() + Send + 'static,
+    P: Fn(String) + Send + 'static,
+{
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+        let result = catch_panic(|| {
+            if event.is_ok_and(|event| is_relevant(&event)) {
+                on_change();
+            }
+        });
+        if let Err(message) = result {
+            on_panic(message);
+        }
+    })?;
+    watcher.watch(root, RecursiveMode::Recursive)?;
+    Ok(watcher)
+}
+
+/// Whether `event` changes what is shown. Reading files, as the preview does,
+/// causes access events, which must not trigger another refresh.
+fn is_relevant(event: &Event) -> bool {
+    !matches!(
+        event.kind,
+        EventKind::Access(_) | EventKind::Modify(ModifyKind::Metadata(_))
+    )
+}
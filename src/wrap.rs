@@ -0,0 +1,165 @@
+//! Soft wrapping for the file view. Widths are display widths, so CJK characters
+//! and emoji take up two columns, and lines are broken after whitespace or
+//! between wide characters where possible.
+
+use std::{borrow::Cow, ops::Range};
+
+use tui::{
+    style::{Color, Style},
+    text::{Span, Spans, Text},
+};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Shown in front of lines that continue the line above.
+pub const MARKER: &str = "↪ ";
+
+/// The byte ranges of the visual lines `line` is wrapped into at `width` columns.
+pub fn wrap_ranges(line: &str, width: usize) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    let mut available = width.max(1);
+    let mut used = 0;
+    // where the current visual line could be broken, and the width before it
+    let mut break_point: Option<(usize, usize)> = None;
+    for (i, c) in line.char_indices() {
+        let char_width = c.width().unwrap_or(0);
+        // the rest carried over may not fit the narrower continuation lines either
+        while used + char_width > available && i > start && !c.is_whitespace() {
+            let (end, end_width) = match break_point.take() {
+                Some((end, end_width)) if end > start => (end, end_width),
+                _ => fitting_end(line, start, i, available),
+            };
+            ranges.push(start..end);
+            start = end;
+            used -= end_width;
+            available = width.saturating_sub(MARKER.width()).max(1);
+        }
+        if char_width > 1 && i > start {
+            break_point = Some((i, used));
+        }
+        used += char_width;
+        if c.is_whitespace() {
+            break_point = Some((i + c.len_utf8(), used));
+        }
+    }
+    ranges.push(start..line.len());
+    ranges
+}
+
+/// The end of the longest part of `line[start..end]` that fits into `available`
+/// columns, but at least one char, together with its width.
+fn fitting_end(line: &str, start: usize, end: usize, available: usize) -> (usize, usize) {
+    let mut fitting = (start, 0);
+    for (i, c) in line[start..end].char_indices() {
+        let width = fitting.1 + c.width().unwrap_or(0);
+        if width > available && fitting.0 > start {
+            break;
+        }
+        fitting = (start + i + c.len_utf8(), width);
+    }
+    fitting
+}
+
+/// How many visual lines `text` takes up when wrapped at `width` columns.
+pub fn visual_line_count(text: &str, width: usize) -> usize {
+    text.lines()
+        .map(|line| wrap_ranges(line, width).len())
+        .sum()
+}
+
+/// The visual line the source line `line` starts at when wrapped at `width` columns.
+pub fn visual_line_of(text: &str, line: usize, width: usize) -> usize {
+    text.lines()
+        .take(line)
+        .map(|line| wrap_ranges(line, width).len())
+        .sum()
+}
+
+/// The source line the visual line `visual_line` belongs to when wrapped at `width` columns.
+pub fn source_line_of(text: &str, visual_line: usize, width: usize) -> usize {
+    let mut visual_lines = 0;
+    for (i, line) in text.lines().enumerate() {
+        visual_lines += wrap_ranges(line, width).len();
+        if visual_lines > visual_line {
+            return i;
+        }
+    }
+    text.lines().count().saturating_sub(1)
+}
+
+/// Wraps the lines of `text`, which have to be the lines of `source` styled, at
+/// `width` columns.
+pub fn wrap<'a>(text: Text<'a>, source: &str, width: usize) -> Text<'a> {
+    let marker_style = Style::default().fg(Color::DarkGray);
+    let mut lines = Vec::with_capacity(text.lines.len());
+    for (spans, source_line) in text.lines.into_iter().zip(source.lines()) {
+        for (i, range) in wrap_ranges(source_line, width).into_iter().enumerate() {
+            let mut visual_line = Vec::new();
+            if i > 0 {
+                visual_line.push(Span::styled(MARKER, marker_style));
+            }
+            visual_line.extend(slice_spans(&spans, range));
+            lines.push(Spans::from(visual_line));
+        }
+    }
+    Text::from(lines)
+}
+
+/// The parts of `spans` that lie within the byte `range` of their concatenated content.
+fn slice_spans<'a>(spans: &Spans<'a>, range: Range<usize>) -> Vec<Span<'a>> {
+    let mut sliced = Vec::new();
+    let mut offset = 0;
+    for span in &spans.0 {
+        let len = span.content.len();
+        let (start, end) = (range.start.max(offset), range.end.min(offset + len));
+        if start < end {
+            let (start, end) = (start - offset, end - offset);
+            let content = match &span.content {
+                Cow::Borrowed(content) => Cow::Borrowed(&content[start..end]),
+                Cow::Owned(content) => Cow::Owned(content[start..end].to_string()),
+            };
+            sliced.push(Span::styled(content, span.style));
+        }
+        offset += len;
+    }
+    sliced
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrapped_lines_fit_their_width() {
+        let lines = [
+            "a bcdefghijk",
+            "abcdefghijklmnopqrstuvwxyz",
+            "the quick brown fox jumps over the lazy dog",
+            "xx yyyyyyyyyyyyyyyyyyyyyyyy zz",
+            "日本語のテキストを折り返す",
+            "a 日本語のテキストを折り返す",
+        ];
+        for line in lines {
+            // narrower than that, a wide char can't fit next to the marker
+            for width in 4..=30 {
+                let ranges = wrap_ranges(line, width);
+                let mut end = 0;
+                for (i, range) in ranges.iter().enumerate() {
+                    assert_eq!(range.start, end, "{:?} at width {}", line, width);
+                    end = range.end;
+                    let marker = if i > 0 { MARKER.width() } else { 0 };
+                    // whitespace may hang over the edge
+                    let text = line[range.clone()].trim_end();
+                    assert!(
+                        marker + text.width() <= width,
+                        "{:?} at width {}: {:?}",
+                        line,
+                        width,
+                        ranges
+                    );
+                }
+                assert_eq!(end, line.len());
+            }
+        }
+    }
+}